@@ -0,0 +1,122 @@
+//! Parser performance benchmarks.
+//!
+//! Run with `cargo bench --features bench`.
+
+use std::fmt::Write as _;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use libpkgconf::bench_internals::parse_str;
+use libpkgconf::fragment::argv_split_posix;
+use libpkgconf::parser::Keyword;
+use std::hint::black_box;
+
+/// A minimal five-line `.pc` file; the parse-cost floor.
+const MINIMAL: &str = "\
+prefix=/usr
+Name: minimal
+Description: minimal baseline package
+Version: 1.0.0
+Cflags: -I${prefix}/include
+";
+
+/// Builds a `.pc` file with `n` independent variables.
+fn variable_heavy(n: usize) -> String {
+    let mut pc = String::new();
+    for i in 0..n {
+        writeln!(pc, "var{i}=/usr/share/value{i}").unwrap();
+    }
+    pc.push_str("Name: heavy\nDescription: variable-heavy package\nVersion: 1.0\n");
+    pc
+}
+
+/// Builds a `.pc` file with `n` variables forming chains `depth` levels deep.
+fn chained(n: usize, depth: usize) -> String {
+    let mut pc = String::from("base=/usr\n");
+    for i in 0..n {
+        let mut prev = "base".to_owned();
+        for d in 0..depth {
+            let name = format!("chain{i}_{d}");
+            writeln!(pc, "{name}=${{{prev}}}/seg{d}").unwrap();
+            prev = name;
+        }
+    }
+    pc.push_str("Name: chained\nDescription: chained variables\nVersion: 1.0\n");
+    pc
+}
+
+/// Builds a `Cflags:` value with `n` flags.
+fn wide_cflags(n: usize) -> String {
+    let mut field = String::new();
+    for i in 0..n {
+        write!(field, "-I/usr/include/dir{i} ").unwrap();
+    }
+    field
+}
+
+/// Compares buffered vs memory-mapped loading of a ~10KB `.pc` file.
+#[cfg(feature = "mmap")]
+fn bench_mmap(c: &mut Criterion) {
+    use libpkgconf::parser::PcFile;
+
+    let mut pc = variable_heavy(50);
+    while pc.len() < 10 * 1024 {
+        let next = pc.len();
+        pc.push_str(&format!("pad{next}=/usr/share/padding/value{next}\n"));
+    }
+    let path = std::env::temp_dir().join("libpkgconf-bench-10k.pc");
+    std::fs::write(&path, &pc).unwrap();
+
+    let mut group = c.benchmark_group("load_10k_pc");
+    group.sample_size(1000);
+    group.bench_function("from_path", |b| {
+        b.iter(|| PcFile::from_path(black_box(&path)).unwrap())
+    });
+    group.bench_function("from_path_mmap", |b| {
+        b.iter(|| PcFile::from_path_mmap(black_box(&path)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_parser(c: &mut Criterion) {
+    let heavy = variable_heavy(50);
+    let chains = parse_str(&chained(2, 10)).unwrap();
+    let cflags = wide_cflags(100);
+
+    c.bench_function("parse_minimal", |b| {
+        b.iter(|| parse_str(black_box(MINIMAL)).unwrap())
+    });
+    c.bench_function("parse_50_variables", |b| {
+        b.iter(|| parse_str(black_box(&heavy)).unwrap())
+    });
+    c.bench_function("resolve_deep_variable_chains", |b| {
+        b.iter(|| black_box(&chains).resolve_variables().unwrap())
+    });
+    c.bench_function("argv_split_100_flags", |b| {
+        b.iter(|| argv_split_posix(black_box(&cflags)).unwrap())
+    });
+
+    // Owned vs Cow field resolution, with and without substitutions.
+    let pc = parse_str(
+        "prefix=/usr\nName: foo\nDescription: d\nVersion: 1.0\n\
+         Cflags: -I${prefix}/include -DFOO\nLibs: -L/usr/lib -lfoo -lbar\n",
+    )
+    .unwrap();
+    c.bench_function("resolve_field_owned_no_subst", |b| {
+        b.iter(|| black_box(&pc).resolve_field(Keyword::Libs).unwrap())
+    });
+    c.bench_function("resolve_field_cow_no_subst", |b| {
+        b.iter(|| black_box(&pc).resolve_field_cow(Keyword::Libs).unwrap())
+    });
+    c.bench_function("resolve_field_owned_subst", |b| {
+        b.iter(|| black_box(&pc).resolve_field(Keyword::Cflags).unwrap())
+    });
+    c.bench_function("resolve_field_cow_subst", |b| {
+        b.iter(|| black_box(&pc).resolve_field_cow(Keyword::Cflags).unwrap())
+    });
+}
+
+#[cfg(feature = "mmap")]
+criterion_group!(benches, bench_parser, bench_mmap);
+#[cfg(not(feature = "mmap"))]
+criterion_group!(benches, bench_parser);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Benchmarks the search-path scan behind `--list-all`, sequential vs
+//! parallel, over a synthetic tree of 500 `.pc` files.
+
+use std::path::PathBuf;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use libpkgconf::client::Client;
+use std::hint::black_box;
+
+/// Writes `count` synthetic `.pc` files and returns a client scanning them.
+fn scan_client(count: usize) -> Client {
+    let dir: PathBuf = std::env::temp_dir().join(format!(
+        "libpkgconf-bench-scan-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for i in 0..count {
+        std::fs::write(
+            dir.join(format!("pkg{i}.pc")),
+            format!(
+                "prefix=/usr\nincludedir=${{prefix}}/include\n\n\
+                 Name: pkg{i}\nVersion: 1.{i}\nDescription: synthetic package {i}\n\
+                 Cflags: -I${{includedir}}/pkg{i}\nLibs: -lpkg{i}\n"
+            ),
+        )
+        .unwrap();
+    }
+    let mut client = Client::new();
+    client.set_search_dirs(&[&dir]);
+    client
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let client = scan_client(500);
+    c.bench_function("list_all_sequential_500", |b| {
+        b.iter(|| {
+            black_box(libpkgconf::bench_internals::list_all_sequential(&client).unwrap())
+        })
+    });
+    #[cfg(feature = "parallel")]
+    c.bench_function("list_all_parallel_500", |b| {
+        b.iter(|| black_box(libpkgconf::bench_internals::list_all_parallel(&client).unwrap()))
+    });
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Version sorting benchmarks: pre-computed sort keys vs repeated
+//! `compare()` calls.
+//!
+//! Run with `cargo bench --features bench`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use libpkgconf::version::{Version, compare, compare_raw, sort_versions_owned};
+use std::hint::black_box;
+
+/// Builds `n` pseudo-random version strings.
+fn versions(n: usize) -> Vec<String> {
+    (0..n)
+        .map(|i| {
+            let major = i % 7;
+            let minor = (i * 13) % 101;
+            let patch = (i * 31) % 17;
+            match i % 4 {
+                0 => format!("{major}.{minor}.{patch}"),
+                1 => format!("{major}.{minor}.{patch}~rc{}", i % 3 + 1),
+                2 => format!("{major}.{minor}{}", (b'a' + (i % 26) as u8) as char),
+                _ => format!("{major}.{minor}.{patch}.{}", i % 1000),
+            }
+        })
+        .collect()
+}
+
+fn bench_version_sort(c: &mut Criterion) {
+    let vs = versions(1000);
+
+    c.bench_function("sort_1000_repeated_compare", |b| {
+        b.iter(|| {
+            let mut vs = black_box(&vs).clone();
+            vs.sort_by(|a, b| compare(a, b));
+            vs
+        })
+    });
+    c.bench_function("sort_1000_precomputed_keys", |b| {
+        b.iter(|| {
+            let mut vs = black_box(&vs).clone();
+            sort_versions_owned(&mut vs);
+            vs
+        })
+    });
+}
+
+fn bench_pairwise_compare(c: &mut Criterion) {
+    let vs = versions(1000);
+
+    c.bench_function("compare_1000_pairs_parsed", |b| {
+        b.iter(|| {
+            for pair in black_box(&vs).windows(2) {
+                let a = Version::parse(&pair[0]).unwrap();
+                let b = Version::parse(&pair[1]).unwrap();
+                black_box(a.cmp(&b));
+            }
+        })
+    });
+    c.bench_function("compare_1000_pairs_raw", |b| {
+        b.iter(|| {
+            for pair in black_box(&vs).windows(2) {
+                black_box(compare_raw(&pair[0], &pair[1]));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_version_sort, bench_pairwise_compare);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+//! Fuzzes the POSIX flag-field tokenizer: must never panic, and every
+//! produced token must be non-empty only when the input had content.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libpkgconf::fragment::argv_split_posix;
+
+fuzz_target!(|data: &str| {
+    if let Ok(tokens) = argv_split_posix(data) {
+        if data.trim().is_empty() {
+            assert!(tokens.is_empty());
+        }
+    }
+});
@@ -0,0 +1,15 @@
+//! Fuzzes the cmd.exe-style flag-field tokenizer: must never panic, and an
+//! input without content must yield no tokens.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libpkgconf::fragment::argv_split_windows;
+
+fuzz_target!(|data: &str| {
+    if let Ok(tokens) = argv_split_windows(data) {
+        if data.trim().is_empty() {
+            assert!(tokens.is_empty());
+        }
+    }
+});
@@ -0,0 +1,22 @@
+//! Fuzzes the `.pc` parser end to end, the way a sysroot scan would hit
+//! it: parse arbitrary input, then expand variables and every field of
+//! whatever parsed. Nothing here may panic or loop forever — the variable
+//! expander's depth limit and the traversal guards are what this exercises.
+//!
+//! Seed the corpus from `fuzz/corpus/pc_file_parse/`, which carries a few
+//! known-good files borrowed from the compat fixtures.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libpkgconf::parser::{Keyword, PcFile};
+
+fuzz_target!(|data: &str| {
+    let Ok(pc) = data.parse::<PcFile>() else {
+        return;
+    };
+    let _ = pc.resolve_variables();
+    for &keyword in Keyword::CANONICAL_ORDER {
+        let _ = pc.resolve_field(keyword);
+    }
+});
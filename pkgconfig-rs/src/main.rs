@@ -0,0 +1,266 @@
+//! A drop-in `pkg-config` replacement built entirely on the public
+//! `libpkgconf` API.
+//!
+//! The binary mirrors the classic CLI: query flags select what is printed
+//! for the packages named on the command line, and the exit status is 0
+//! when every package resolves (and every version check passes), 1
+//! otherwise.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use libpkgconf::PKGCONFIG_COMPAT_VERSION;
+use libpkgconf::client::Client;
+use libpkgconf::fragment::{FragmentList, RenderOptions};
+use libpkgconf::version::{Comparator, VersionReq};
+
+/// The parsed command line.
+#[derive(Debug, Default)]
+struct Options {
+    cflags: bool,
+    libs: bool,
+    modversion: bool,
+    exists: bool,
+    print_requires: bool,
+    print_requires_private: bool,
+    print_provides: bool,
+    list_all: bool,
+    print_version: bool,
+    static_linking: bool,
+    msvc_syntax: bool,
+    print0: bool,
+    env_only: bool,
+    variable: Option<String>,
+    defines: Vec<(String, String)>,
+    atleast_version: Option<String>,
+    exact_version: Option<String>,
+    max_version: Option<String>,
+    atleast_pkgconfig_version: Option<String>,
+    packages: Vec<String>,
+}
+
+impl Options {
+    /// Parses the argument list, accepting both `--flag=value` and
+    /// `--flag value` spellings for valued options.
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        fn value_for(
+            args: &mut impl Iterator<Item = String>,
+            flag: &str,
+            inline: Option<&str>,
+        ) -> Result<String, String> {
+            match inline {
+                Some(value) => Ok(value.to_owned()),
+                None => args
+                    .next()
+                    .ok_or_else(|| format!("option '{flag}' requires a value")),
+            }
+        }
+        let mut options = Options::default();
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg.clone(), None),
+            };
+            match flag.as_str() {
+                "--cflags" => options.cflags = true,
+                "--libs" => options.libs = true,
+                "--modversion" => options.modversion = true,
+                "--exists" => options.exists = true,
+                "--print-requires" => options.print_requires = true,
+                "--print-requires-private" => options.print_requires_private = true,
+                "--print-provides" => options.print_provides = true,
+                "--list-all" => options.list_all = true,
+                "--version" => options.print_version = true,
+                "--static" => options.static_linking = true,
+                "--msvc-syntax" => options.msvc_syntax = true,
+                "--print0" => options.print0 = true,
+                "--env-only" => options.env_only = true,
+                "--variable" => {
+                    options.variable = Some(value_for(&mut args, &flag, inline.as_deref())?);
+                }
+                "--define-variable" => {
+                    let pair = value_for(&mut args, &flag, inline.as_deref())?;
+                    let Some((name, value)) = pair.split_once('=') else {
+                        return Err(format!("malformed --define-variable value '{pair}'"));
+                    };
+                    options.defines.push((name.to_owned(), value.to_owned()));
+                }
+                "--atleast-version" => {
+                    options.atleast_version = Some(value_for(&mut args, &flag, inline.as_deref())?);
+                }
+                "--exact-version" => {
+                    options.exact_version = Some(value_for(&mut args, &flag, inline.as_deref())?);
+                }
+                "--max-version" => {
+                    options.max_version = Some(value_for(&mut args, &flag, inline.as_deref())?);
+                }
+                "--atleast-pkgconfig-version" => {
+                    options.atleast_pkgconfig_version =
+                        Some(value_for(&mut args, &flag, inline.as_deref())?);
+                }
+                _ if flag.starts_with("--") => {
+                    return Err(format!("unknown option '{flag}'"));
+                }
+                _ => options.packages.push(arg),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("pkg-config: {message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            if !options.exists {
+                eprintln!("pkg-config: {message}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Executes the query; `Ok(false)` is a silent failure exit (used by the
+/// version predicates), `Err` carries a diagnostic.
+fn run(options: &Options) -> Result<bool, String> {
+    if options.print_version {
+        println!("{PKGCONFIG_COMPAT_VERSION}");
+        return Ok(true);
+    }
+    let mut client = build_client(options);
+    if let Some(required) = &options.atleast_pkgconfig_version {
+        return Ok(client.atleast_pkgconfig_version(required));
+    }
+    for (name, value) in &options.defines {
+        client.set_global_var(name, value);
+    }
+    client.set_static(options.static_linking);
+
+    if options.list_all {
+        for (name, version) in client.list_all_packages().map_err(|err| err.to_string())? {
+            println!("{name} {version}");
+        }
+        return Ok(true);
+    }
+    if options.packages.is_empty() {
+        return Err("no packages given on the command line".to_owned());
+    }
+
+    let mut cflags = FragmentList::new();
+    let mut libs = FragmentList::new();
+    for name in &options.packages {
+        let package = client
+            .resolve_package(name, None)
+            .map_err(|err| err.to_string())?;
+        for (comparator, version) in [
+            (Comparator::GreaterOrEqual, &options.atleast_version),
+            (Comparator::Equal, &options.exact_version),
+            (Comparator::LessOrEqual, &options.max_version),
+        ] {
+            if let Some(version) = version {
+                let req = VersionReq {
+                    comparator,
+                    version: version.clone(),
+                };
+                if !package.version_matches(&req) {
+                    return Ok(false);
+                }
+            }
+        }
+        if options.modversion {
+            println!("{}", client.modversion(name).map_err(|err| err.to_string())?);
+        }
+        if let Some(variable) = &options.variable {
+            let value = client
+                .print_variable(name, variable)
+                .map_err(|err| err.to_string())?;
+            println!("{value}");
+        }
+        if options.print_requires {
+            println!("{}", client.print_requires(name).map_err(|err| err.to_string())?);
+        }
+        if options.print_requires_private {
+            println!(
+                "{}",
+                client
+                    .print_requires_private(name)
+                    .map_err(|err| err.to_string())?
+            );
+        }
+        if options.print_provides {
+            println!("{} = {}", package.id(), package.version);
+            if let Some(provides) = package.provides() {
+                println!("{provides}");
+            }
+        }
+        if options.cflags {
+            cflags = cflags.merge(client.cflags_for(name).map_err(|err| err.to_string())?);
+        }
+        if options.libs {
+            libs = libs.merge(client.libs_for(name).map_err(|err| err.to_string())?);
+        }
+    }
+
+    if options.print0 {
+        // NUL-separated output for xargs -0 style consumers; fragments with
+        // embedded spaces stay intact.
+        let mut out = Vec::new();
+        if options.cflags {
+            out.extend_from_slice(&cflags.render_nul_separated());
+        }
+        if options.libs {
+            out.extend_from_slice(&libs.render_nul_separated());
+        }
+        if !out.is_empty() {
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(&out)
+                .map_err(|err| err.to_string())?;
+        }
+        return Ok(true);
+    }
+
+    let render = RenderOptions {
+        msvc_syntax: options.msvc_syntax,
+        ..RenderOptions::default()
+    };
+    let mut flag_output = Vec::new();
+    if options.cflags {
+        flag_output.push(cflags.render_with_options(&render));
+    }
+    if options.libs {
+        flag_output.push(libs.render_with_options(&render));
+    }
+    if !flag_output.is_empty() {
+        println!("{}", flag_output.join(" "));
+    }
+    Ok(true)
+}
+
+/// Builds the client, honouring `--env-only` by restricting the search
+/// path to the directories named in the environment.
+fn build_client(options: &Options) -> Client {
+    let client = Client::from_env();
+    if !options.env_only {
+        return client;
+    }
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for var in ["PKG_CONFIG_PATH", "PKG_CONFIG_LIBDIR"] {
+        if let Some(value) = std::env::var_os(var) {
+            dirs.extend(std::env::split_paths(&value));
+        }
+    }
+    let dirs: Vec<&std::path::Path> = dirs.iter().map(PathBuf::as_path).collect();
+    let mut client = client;
+    client.set_search_dirs(&dirs);
+    client
+}
@@ -0,0 +1,167 @@
+//! Resolution tracing.
+//!
+//! pkgconf writes a log of every package lookup when `PKG_CONFIG_LOG` is
+//! set; an [`AuditLog`] generalises that to either a log file or an
+//! in-process callback, so build systems can trace and time resolution
+//! without scraping stderr.
+
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A single step in package resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// A package name was looked up in the search path.
+    PackageLookup {
+        /// The requested package name.
+        name: String,
+        /// Whether a `.pc` file was found.
+        found: bool,
+        /// The resolved path, when found.
+        path: Option<PathBuf>,
+    },
+    /// A package's version was checked against a requirement.
+    VersionCheck {
+        /// The requested package name.
+        name: String,
+        /// The version the package declares.
+        found: String,
+        /// The requirement it was checked against.
+        required: String,
+        /// Whether the check passed.
+        passed: bool,
+    },
+    /// A `Requires:` edge was followed during traversal.
+    DependencyTraversal {
+        /// The package whose requirement is being followed.
+        from: String,
+        /// The required package.
+        to: String,
+        /// The traversal depth of the edge, starting at 1.
+        depth: u32,
+    },
+}
+
+impl fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditEvent::PackageLookup { name, found, path } => match path {
+                Some(path) => write!(f, "lookup {name}: {} ({})", found, path.display()),
+                None => write!(f, "lookup {name}: {found}"),
+            },
+            AuditEvent::VersionCheck {
+                name,
+                found,
+                required,
+                passed,
+            } => write!(f, "version {name}: {found} vs '{required}': {passed}"),
+            AuditEvent::DependencyTraversal { from, to, depth } => {
+                write!(f, "traverse {from} -> {to} (depth {depth})")
+            }
+        }
+    }
+}
+
+/// Where audit events go.
+enum Sink {
+    File(Mutex<File>),
+    Callback(Box<dyn Fn(AuditEvent) + Send + Sync>),
+}
+
+impl fmt::Debug for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sink::File(_) => f.write_str("Sink::File"),
+            Sink::Callback(_) => f.write_str("Sink::Callback"),
+        }
+    }
+}
+
+/// A destination for [`AuditEvent`]s emitted during resolution.
+#[derive(Debug)]
+pub struct AuditLog {
+    sink: Sink,
+}
+
+impl AuditLog {
+    /// Opens (or creates) a log file that receives one line per event,
+    /// matching the `PKG_CONFIG_LOG` behaviour of pkgconf.
+    pub fn new_file(path: &Path) -> Result<AuditLog, std::io::Error> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(AuditLog {
+            sink: Sink::File(Mutex::new(file)),
+        })
+    }
+
+    /// Routes events to an in-process callback instead of a file.
+    pub fn new_callback(f: impl Fn(AuditEvent) + Send + Sync + 'static) -> AuditLog {
+        AuditLog {
+            sink: Sink::Callback(Box::new(f)),
+        }
+    }
+
+    /// Emits one event. File write errors are deliberately swallowed —
+    /// tracing must never fail the resolution it observes.
+    pub fn record(&self, event: AuditEvent) {
+        match &self.sink {
+            Sink::File(file) => {
+                let mut file = file.lock().unwrap();
+                let _ = writeln!(file, "{event}");
+            }
+            Sink::Callback(callback) => callback(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn callback_sink_receives_events() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let log = AuditLog::new_callback(move |event| sink.lock().unwrap().push(event));
+        log.record(AuditEvent::PackageLookup {
+            name: "foo".to_owned(),
+            found: false,
+            path: None,
+        });
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(matches!(
+            &seen[0],
+            AuditEvent::PackageLookup { name, found: false, .. } if name == "foo"
+        ));
+    }
+
+    #[test]
+    fn file_sink_writes_one_line_per_event() {
+        let path = std::env::temp_dir().join(format!(
+            "libpkgconf-audit-{}.log",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let log = AuditLog::new_file(&path).unwrap();
+        log.record(AuditEvent::DependencyTraversal {
+            from: "app".to_owned(),
+            to: "base".to_owned(),
+            depth: 1,
+        });
+        log.record(AuditEvent::VersionCheck {
+            name: "base".to_owned(),
+            found: "1.0".to_owned(),
+            required: ">= 1.0".to_owned(),
+            passed: true,
+        });
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "traverse app -> base (depth 1)");
+        assert_eq!(lines[1], "version base: 1.0 vs '>= 1.0': true");
+    }
+}
@@ -0,0 +1,235 @@
+//! Cargo build-script integration.
+//!
+//! Only available with the `build-script` feature. From a `build.rs`,
+//! [`cargo_pkg_config`] resolves a package and prints the
+//! `cargo:rustc-link-lib=`/`cargo:rustc-link-search=` directives Cargo
+//! expects, making `libpkgconf` a drop-in replacement for the `pkg-config`
+//! crate. [`CargoConfig`] is the builder behind it for version bounds and
+//! static linking.
+
+use std::fmt;
+
+use crate::client::Client;
+use crate::fragment::{FragmentList, FragmentType};
+use crate::parser::ParseError;
+
+/// An error produced while probing a package from a build script.
+#[derive(Debug)]
+pub enum BuildError {
+    /// `TARGET` and `HOST` differ and `PKG_CONFIG_ALLOW_CROSS` is not set.
+    CrossCompilationDisallowed,
+    /// The package could not be resolved.
+    Resolve(ParseError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::CrossCompilationDisallowed => write!(
+                f,
+                "pkg-config cannot be used when cross-compiling unless PKG_CONFIG_ALLOW_CROSS is set"
+            ),
+            BuildError::Resolve(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Resolve(err) => Some(err),
+            BuildError::CrossCompilationDisallowed => None,
+        }
+    }
+}
+
+impl From<ParseError> for BuildError {
+    fn from(err: ParseError) -> Self {
+        BuildError::Resolve(err)
+    }
+}
+
+/// Builder for build-script probes, mirroring the knobs of the
+/// `pkg-config` crate.
+#[derive(Debug, Clone, Default)]
+pub struct CargoConfig {
+    min_version: Option<String>,
+    max_version: Option<String>,
+    static_linking: bool,
+    crate_name: Option<String>,
+}
+
+impl CargoConfig {
+    /// Creates a probe configuration with no version bounds.
+    pub fn new() -> CargoConfig {
+        CargoConfig::default()
+    }
+
+    /// Requires the package to be at least `version`.
+    pub fn min_version(mut self, version: &str) -> Self {
+        self.min_version = Some(version.to_owned());
+        self
+    }
+
+    /// Requires the package to be at most `version`.
+    pub fn max_version(mut self, version: &str) -> Self {
+        self.max_version = Some(version.to_owned());
+        self
+    }
+
+    /// Links the package statically, folding in its `.private` fields.
+    pub fn statik(mut self, static_linking: bool) -> Self {
+        self.static_linking = static_linking;
+        self
+    }
+
+    /// Overrides the crate name used for the `<NAME>_NO_PKG_CONFIG`
+    /// escape-hatch environment variable; defaults to the package name.
+    pub fn crate_name(mut self, name: &str) -> Self {
+        self.crate_name = Some(name.to_owned());
+        self
+    }
+
+    /// Resolves `package` and prints the Cargo directives to stdout.
+    ///
+    /// Cross-compilation (Cargo's `TARGET` differing from `HOST`) is
+    /// refused unless `PKG_CONFIG_ALLOW_CROSS` is set, matching the
+    /// behaviour build scripts already rely on. Setting
+    /// `<NAME>_NO_PKG_CONFIG` skips the probe entirely.
+    pub fn probe(&self, package: &str) -> Result<(), BuildError> {
+        let escape_hatch = format!("{}_NO_PKG_CONFIG", env_prefix(self.crate_name.as_deref().unwrap_or(package)));
+        println!("cargo:rerun-if-env-changed={escape_hatch}");
+        if std::env::var_os(&escape_hatch).is_some() {
+            return Ok(());
+        }
+        if is_cross_compiling() && std::env::var_os("PKG_CONFIG_ALLOW_CROSS").is_none() {
+            return Err(BuildError::CrossCompilationDisallowed);
+        }
+        for line in self.directives(package, &Client::from_env())? {
+            println!("{line}");
+        }
+        Ok(())
+    }
+
+    /// Computes the directive lines for `package` without printing them.
+    fn directives(&self, package: &str, client: &Client) -> Result<Vec<String>, BuildError> {
+        let mut client = client.clone();
+        client.set_static(self.static_linking);
+        let version_req = match (&self.min_version, &self.max_version) {
+            (Some(min), Some(max)) => Some(format!(">= {min}, <= {max}")),
+            (Some(min), None) => Some(format!(">= {min}")),
+            (None, Some(max)) => Some(format!("<= {max}")),
+            (None, None) => None,
+        };
+        client.resolve_package(package, version_req.as_deref())?;
+        let cflags = client.cflags_for(package)?;
+        let libs = client.libs_for(package)?;
+        let mut lines = vec![
+            "cargo:rerun-if-env-changed=PKG_CONFIG_PATH".to_owned(),
+            "cargo:rerun-if-env-changed=PKG_CONFIG_LIBDIR".to_owned(),
+            "cargo:rerun-if-env-changed=PKG_CONFIG_SYSROOT_DIR".to_owned(),
+        ];
+        let kind = if self.static_linking { "static=" } else { "" };
+        lines.extend(directive_lines(&libs, FragmentType::LibraryPath, "cargo:rustc-link-search=native="));
+        lines.extend(directive_lines(&libs, FragmentType::Library, &format!("cargo:rustc-link-lib={kind}")));
+        lines.extend(directive_lines(&cflags, FragmentType::IncludePath, "cargo:include="));
+        Ok(lines)
+    }
+}
+
+/// Resolves `package` with default configuration and prints the Cargo
+/// directives to stdout.
+pub fn cargo_pkg_config(package: &str) -> Result<(), BuildError> {
+    CargoConfig::new().probe(package)
+}
+
+/// Uppercases a package name into the conventional env-var prefix.
+fn env_prefix(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Whether Cargo reports a cross build (`TARGET` differs from `HOST`).
+fn is_cross_compiling() -> bool {
+    match (std::env::var("TARGET"), std::env::var("HOST")) {
+        (Ok(target), Ok(host)) => target != host,
+        _ => false,
+    }
+}
+
+/// Renders one directive line per fragment of `kind`, with the fragment's
+/// value (not its flag prefix) appended to `prefix`.
+fn directive_lines(fragments: &FragmentList, kind: FragmentType, prefix: &str) -> Vec<String> {
+    fragments
+        .filter_by_kind(kind)
+        .iter()
+        .map(|fragment| format!("{prefix}{}", fragment.value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe_client(label: &str) -> Client {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-build-{label}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 1.4\nDescription: d\n\
+             Cflags: -I/opt/foo/include\nLibs: -L/opt/foo/lib -lfoo\nLibs.private: -lm\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        client
+    }
+
+    #[test]
+    fn directives_cover_search_lib_and_include() {
+        let client = probe_client("basic");
+        let lines = CargoConfig::new().directives("foo", &client).unwrap();
+        assert!(lines.contains(&"cargo:rustc-link-search=native=/opt/foo/lib".to_owned()));
+        assert!(lines.contains(&"cargo:rustc-link-lib=foo".to_owned()));
+        assert!(lines.contains(&"cargo:include=/opt/foo/include".to_owned()));
+        assert!(lines.contains(&"cargo:rerun-if-env-changed=PKG_CONFIG_PATH".to_owned()));
+    }
+
+    #[test]
+    fn static_probe_emits_static_link_kind() {
+        let client = probe_client("static");
+        let lines = CargoConfig::new().statik(true).directives("foo", &client).unwrap();
+        assert!(lines.contains(&"cargo:rustc-link-lib=static=foo".to_owned()));
+        assert!(lines.contains(&"cargo:rustc-link-lib=static=m".to_owned()));
+    }
+
+    #[test]
+    fn version_bounds_are_enforced() {
+        let client = probe_client("versions");
+        assert!(CargoConfig::new().min_version("1.0").directives("foo", &client).is_ok());
+        let err = CargoConfig::new()
+            .min_version("2.0")
+            .directives("foo", &client)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BuildError::Resolve(ParseError::VersionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn env_prefix_uppercases_and_sanitises() {
+        assert_eq!(env_prefix("libfoo-2.0"), "LIBFOO_2_0");
+    }
+}
@@ -0,0 +1,191 @@
+//! Parsed-package caching.
+//!
+//! A `.pc` file that appears in multiple dependency chains would otherwise
+//! be read and parsed once per chain; a [`PackageCache`] keyed on the
+//! package name (the stem of the `.pc` file) collapses that to a single
+//! parse. [`Client`](crate::client::Client) holds one behind an
+//! `Arc<Mutex<_>>` so clones of a client share it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::parser::PcFile;
+
+/// Hit/miss counters for a [`PackageCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that fell through to disk.
+    pub misses: u64,
+}
+
+/// A cached file together with where it came from, for staleness checks.
+#[derive(Debug)]
+struct CacheEntry {
+    pc: PcFile,
+    /// The source path and its modification time at load time; `None` for
+    /// entries inserted from memory, which never go stale.
+    source: Option<(PathBuf, SystemTime)>,
+}
+
+/// A cache of parsed `.pc` files, keyed by package name.
+#[derive(Debug, Default)]
+pub struct PackageCache {
+    entries: HashMap<String, CacheEntry>,
+    stats: CacheStats,
+}
+
+impl PackageCache {
+    /// Creates an empty cache.
+    pub fn new() -> PackageCache {
+        PackageCache::default()
+    }
+
+    /// Stores the parsed file for `name`, replacing any previous entry.
+    ///
+    /// Entries inserted this way carry no source path and are never
+    /// considered stale; use [`PackageCache::insert_from_path`] for files
+    /// loaded from disk.
+    pub fn insert(&mut self, name: String, pc: PcFile) {
+        self.entries.insert(name, CacheEntry { pc, source: None });
+    }
+
+    /// Stores the parsed file for `name` along with its source path and
+    /// current modification time, enabling staleness checks via
+    /// [`PackageCache::get_valid`].
+    pub fn insert_from_path(&mut self, name: String, pc: PcFile, path: &Path) {
+        let source = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(|mtime| (path.to_path_buf(), mtime));
+        self.entries.insert(name, CacheEntry { pc, source });
+    }
+
+    /// Looks up `name`, counting the outcome in [`PackageCache::stats`].
+    pub fn get(&mut self, name: &str) -> Option<&PcFile> {
+        match self.entries.get(name) {
+            Some(entry) => {
+                self.stats.hits += 1;
+                Some(&entry.pc)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Like [`PackageCache::get`], but evicts and misses when the source
+    /// file has been modified (or deleted) since it was cached.
+    pub fn get_valid(&mut self, name: &str) -> Option<&PcFile> {
+        let stale = self.entries.get(name).is_some_and(|entry| {
+            entry.source.as_ref().is_some_and(|(path, mtime)| {
+                std::fs::metadata(path)
+                    .and_then(|meta| meta.modified())
+                    .map(|current| current != *mtime)
+                    .unwrap_or(true)
+            })
+        });
+        if stale {
+            self.entries.remove(name);
+        }
+        self.get(name)
+    }
+
+    /// Looks up `name` without consulting the filesystem; a stale entry is
+    /// still returned.
+    pub fn get_unchecked(&mut self, name: &str) -> Option<&PcFile> {
+        self.get(name)
+    }
+
+    /// Whether an entry for `name` exists, without touching the counters.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Drops the entry for `name`, forcing the next lookup to re-read the
+    /// file. Returns the evicted `.pc` data, if any was cached.
+    pub fn remove(&mut self, name: &str) -> Option<PcFile> {
+        self.entries.remove(name).map(|entry| entry.pc)
+    }
+
+    /// Drops every entry and resets the counters.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stats = CacheStats::default();
+    }
+
+    /// The hit/miss counters accumulated since the last clear.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pc(name: &str) -> PcFile {
+        PcFile::new(name, "1.0", "d")
+    }
+
+    #[test]
+    fn get_counts_hits_and_misses() {
+        let mut cache = PackageCache::new();
+        assert!(cache.get("foo").is_none());
+        cache.insert("foo".to_owned(), pc("foo"));
+        assert!(cache.contains("foo"));
+        assert_eq!(cache.get("foo").unwrap().name(), Some("foo"));
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn insert_replaces_an_existing_entry() {
+        let mut cache = PackageCache::new();
+        cache.insert("foo".to_owned(), pc("foo"));
+        cache.insert("foo".to_owned(), PcFile::new("foo", "2.0", "d"));
+        assert_eq!(cache.get("foo").unwrap().version(), Some("2.0"));
+    }
+
+    #[test]
+    fn get_valid_evicts_a_modified_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-cache-mtime-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("foo.pc");
+        std::fs::write(&path, "Name: foo\nVersion: 1.0\nDescription: d\n").unwrap();
+        let mut cache = PackageCache::new();
+        cache.insert_from_path("foo".to_owned(), PcFile::from_path(&path).unwrap(), &path);
+        assert!(cache.get_valid("foo").is_some());
+        // Rewrite the file and push its mtime forward past the stored one.
+        std::fs::write(&path, "Name: foo\nVersion: 2.0\nDescription: d\n").unwrap();
+        let file = std::fs::File::options().append(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        assert!(cache.get_unchecked("foo").is_some());
+        assert!(cache.get_valid("foo").is_none());
+        assert!(!cache.contains("foo"));
+    }
+
+    #[test]
+    fn in_memory_entries_are_never_stale() {
+        let mut cache = PackageCache::new();
+        cache.insert("foo".to_owned(), pc("foo"));
+        assert!(cache.get_valid("foo").is_some());
+    }
+
+    #[test]
+    fn clear_resets_entries_and_stats() {
+        let mut cache = PackageCache::new();
+        cache.insert("foo".to_owned(), pc("foo"));
+        cache.get("foo");
+        cache.clear();
+        assert!(!cache.contains("foo"));
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+}
@@ -0,0 +1,1872 @@
+//! Environment-driven package resolution.
+//!
+//! A [`Client`] carries the configuration a `pkg-config` invocation would
+//! take from its environment — search paths, sysroot, variable overrides
+//! and filtering switches — and resolves package queries against it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::audit::{AuditEvent, AuditLog};
+use crate::cache::PackageCache;
+use crate::error::{PkgconfError, Warning};
+use crate::fragment::{FragmentList, FragmentType};
+use crate::parser::{Keyword, ParseError, PcFile, ResolveOptions};
+use crate::personality::Personality;
+use crate::pkg::{Package, TraversalState};
+use crate::DEFAULT_MAX_TRAVERSAL_DEPTH;
+
+/// How client query results are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientOutputFormat {
+    /// Space-separated flag strings, as the `pkg-config` binary prints.
+    #[default]
+    Text,
+    /// Structured JSON, for IDEs and other machine consumers.
+    Json,
+}
+
+/// Whether private dependency edges take part in flag collection.
+///
+/// Static binaries must link everything their dependencies link, so
+/// [`StaticMode::Static`] folds `Requires.private:` and `Libs.private:`
+/// into the traversal; [`StaticMode::Dynamic`] sticks to the public
+/// fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticMode {
+    /// Follow `Requires.private:` edges and include `Libs.private:`.
+    Static,
+    /// Public `Requires:` edges and fields only.
+    Dynamic,
+}
+
+/// A configured package resolver.
+///
+/// [`Client::default`] mirrors a stock installation; [`Client::from_env`]
+/// additionally honours the `PKG_CONFIG_*` environment variables the way
+/// the `pkg-config` binary does.
+#[derive(Debug, Clone)]
+pub struct Client {
+    search_paths: Vec<PathBuf>,
+    sysroot_dir: Option<PathBuf>,
+    global_vars: HashMap<String, String>,
+    system_includedirs: Vec<String>,
+    system_libdirs: Vec<String>,
+    allow_system_cflags: bool,
+    allow_system_libs: bool,
+    static_linking: bool,
+    disable_uninstalled: bool,
+    relocate_paths: bool,
+    max_depth: i32,
+    cache: Arc<Mutex<PackageCache>>,
+    audit: Option<Arc<AuditLog>>,
+    warnings: Arc<Mutex<Vec<Warning>>>,
+    last_error: Arc<Mutex<Option<PkgconfError>>>,
+}
+
+impl Default for Client {
+    /// A client configured from [`Personality::default_for_host`], so the
+    /// platform-specific paths live in one place.
+    fn default() -> Client {
+        let host = Personality::default_for_host();
+        Client {
+            search_paths: host.pkg_config_path,
+            sysroot_dir: host.sysroot_dir,
+            global_vars: HashMap::new(),
+            system_includedirs: host
+                .system_includedirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect(),
+            system_libdirs: host
+                .system_libdirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect(),
+            allow_system_cflags: false,
+            allow_system_libs: false,
+            static_linking: false,
+            disable_uninstalled: false,
+            relocate_paths: false,
+            max_depth: DEFAULT_MAX_TRAVERSAL_DEPTH,
+            cache: Arc::new(Mutex::new(PackageCache::new())),
+            audit: None,
+            warnings: Arc::new(Mutex::new(Vec::new())),
+            last_error: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl Client {
+    /// Creates a client with stock configuration, ignoring the environment.
+    pub fn new() -> Client {
+        Client::default()
+    }
+
+    /// Routes an audit event of every lookup, version check and traversal
+    /// step to `log`.
+    pub fn with_audit(mut self, log: AuditLog) -> Self {
+        self.audit = Some(Arc::new(log));
+        self
+    }
+
+    /// Emits `event` to the configured audit log, if any.
+    fn audit(&self, event: AuditEvent) {
+        if let Some(log) = &self.audit {
+            log.record(event);
+        }
+    }
+
+    /// Applies a cross-compilation personality: its search paths, system
+    /// directories and sysroot replace the client's, except that empty
+    /// personality fields leave the current configuration untouched.
+    pub fn with_personality(mut self, personality: Personality) -> Self {
+        if !personality.pkg_config_path.is_empty() {
+            self.search_paths = personality.pkg_config_path;
+        }
+        if !personality.system_includedirs.is_empty() {
+            self.system_includedirs = personality
+                .system_includedirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
+        }
+        if !personality.system_libdirs.is_empty() {
+            self.system_libdirs = personality
+                .system_libdirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
+        }
+        if personality.sysroot_dir.is_some() {
+            self.sysroot_dir = personality.sysroot_dir;
+        }
+        self
+    }
+
+    /// Lowers (or raises) the dependency traversal depth limit, which
+    /// defaults to [`DEFAULT_MAX_TRAVERSAL_DEPTH`].
+    pub fn with_max_depth(mut self, depth: i32) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// The configured dependency traversal depth limit.
+    pub(crate) fn max_depth(&self) -> i32 {
+        self.max_depth
+    }
+
+    /// Creates a client configured from the `PKG_CONFIG_*` environment
+    /// variables.
+    ///
+    /// `PKG_CONFIG_LIBDIR` replaces the default search path entirely;
+    /// `PKG_CONFIG_PATH` entries are searched before it. Both are
+    /// colon-separated lists. `PKG_CONFIG_SYSROOT_DIR`,
+    /// `PKG_CONFIG_ALLOW_SYSTEM_CFLAGS`, `PKG_CONFIG_ALLOW_SYSTEM_LIBS`
+    /// and `PKG_CONFIG_DISABLE_UNINSTALLED` map to the corresponding
+    /// fields.
+    pub fn from_env() -> Client {
+        let mut client = Client::default();
+        if let Some(libdir) = std::env::var_os("PKG_CONFIG_LIBDIR") {
+            client.search_paths = std::env::split_paths(&libdir).collect();
+        }
+        if let Some(path) = std::env::var_os("PKG_CONFIG_PATH") {
+            let mut paths: Vec<PathBuf> = std::env::split_paths(&path).collect();
+            paths.append(&mut client.search_paths);
+            client.search_paths = paths;
+        }
+        if let Some(sysroot) = std::env::var_os("PKG_CONFIG_SYSROOT_DIR") {
+            client.sysroot_dir = Some(PathBuf::from(sysroot));
+        }
+        client.allow_system_cflags = std::env::var_os("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS").is_some();
+        client.allow_system_libs = std::env::var_os("PKG_CONFIG_ALLOW_SYSTEM_LIBS").is_some();
+        client.disable_uninstalled =
+            std::env::var_os("PKG_CONFIG_DISABLE_UNINSTALLED").is_some();
+        client.relocate_paths = std::env::var_os("PKG_CONFIG_RELOCATE_PATHS").is_some()
+            && std::env::var_os("PKG_CONFIG_DONT_RELOCATE_PATHS").is_none();
+        if let Ok(depth) = std::env::var("PKG_CONFIG_MAXIMUM_TRAVERSE_DEPTH")
+            && let Ok(depth) = depth.parse()
+        {
+            client.max_depth = depth;
+        }
+        if let Some(log_path) = std::env::var_os("PKG_CONFIG_LOG")
+            && let Ok(log) = AuditLog::new_file(std::path::Path::new(&log_path))
+        {
+            client.audit = Some(Arc::new(log));
+        }
+        client
+    }
+
+    /// Locates and parses the `.pc` file for `name`.
+    ///
+    /// Directories in [`Client::search_paths`] are tried in order and the
+    /// first hit wins. Within a directory, an `<name>-uninstalled.pc`
+    /// variant is preferred over `<name>.pc` unless uninstalled packages
+    /// are disabled. Paths are canonicalised before parsing so symlinked
+    /// search directories resolve correctly.
+    pub fn find_package(&self, name: &str) -> Result<PcFile, ParseError> {
+        match self.locate_package(name)? {
+            Some(path) => PcFile::from_path(&path),
+            None => Err(ParseError::PackageNotFound {
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    /// Resolves `name` to the canonicalised path of its `.pc` file, or
+    /// `Ok(None)` when no search directory contains it.
+    fn locate_package(&self, name: &str) -> Result<Option<PathBuf>, ParseError> {
+        for dir in &self.search_paths {
+            let installed = dir.join(format!("{name}.pc"));
+            let mut candidates = Vec::with_capacity(2);
+            if !self.disable_uninstalled {
+                // An -uninstalled counterpart shadows the installed file,
+                // and is also found when named directly.
+                match PcFile::uninstalled_path(&installed) {
+                    Some(uninstalled) => candidates.push(uninstalled),
+                    None => candidates.push(dir.join(format!("{name}-uninstalled.pc"))),
+                }
+            }
+            candidates.push(installed);
+            for candidate in candidates {
+                if candidate.is_file() {
+                    let path = std::fs::canonicalize(&candidate).unwrap_or(candidate);
+                    self.audit(AuditEvent::PackageLookup {
+                        name: name.to_owned(),
+                        found: true,
+                        path: Some(path.clone()),
+                    });
+                    return Ok(Some(path));
+                }
+            }
+        }
+        self.audit(AuditEvent::PackageLookup {
+            name: name.to_owned(),
+            found: false,
+            path: None,
+        });
+        Ok(None)
+    }
+
+    /// Like [`Client::find_package`], but consults the package cache first
+    /// so each `.pc` file is read and parsed at most once per client.
+    pub(crate) fn load_package(&self, name: &str) -> Result<PcFile, ParseError> {
+        if let Some(pc) = self.cache.lock().unwrap().get(name) {
+            return Ok(pc.clone());
+        }
+        let Some(path) = self.locate_package(name)? else {
+            return Err(ParseError::PackageNotFound {
+                name: name.to_owned(),
+            });
+        };
+        let pc = PcFile::from_path(&path)?;
+        self.lint(name, &pc);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert_from_path(name.to_owned(), pc.clone(), &path);
+        Ok(pc)
+    }
+
+    /// Records non-fatal diagnostics for a freshly loaded `.pc` file.
+    ///
+    /// Runs only on cache misses, so each warning is reported once per
+    /// package per client.
+    fn lint(&self, name: &str, pc: &PcFile) {
+        let mut warnings = self.warnings.lock().unwrap();
+        if pc.description().is_none_or(str::is_empty) {
+            warnings.push(Warning::MissingDescription(name.to_owned()));
+        }
+        let version_ok = pc
+            .version()
+            .is_some_and(|version| version.starts_with(|c: char| c.is_ascii_digit()));
+        if !version_ok {
+            warnings.push(Warning::SuspiciousVersion(name.to_owned()));
+        }
+        for keyword in [Keyword::Requires, Keyword::RequiresPrivate] {
+            let Ok(Some(field)) = pc.resolve_field(keyword) else {
+                continue;
+            };
+            let deps = crate::pkg::dependency_names(&field);
+            let mut seen = std::collections::HashSet::new();
+            for dep in deps {
+                if !seen.insert(dep.clone()) {
+                    warnings.push(Warning::DuplicateDependency {
+                        package: name.to_owned(),
+                        dependency: dep,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Empties and returns the accumulated non-fatal diagnostics.
+    pub fn drain_warnings(&self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings.lock().unwrap())
+    }
+
+    /// The accumulated non-fatal diagnostics, without clearing them.
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.lock().unwrap().clone()
+    }
+
+    /// The shared package cache backing this client's lookups.
+    pub fn cache(&self) -> &Arc<Mutex<PackageCache>> {
+        &self.cache
+    }
+
+    /// Loads `name` and its transitive `Requires:` closure, depth-first,
+    /// dependents before dependencies. `Requires.private` edges are
+    /// followed only when `include_private` is set.
+    fn collect_transitive(
+        &self,
+        name: &str,
+        include_private: bool,
+    ) -> Result<Vec<PcFile>, ParseError> {
+        let mut state = TraversalState::default();
+        let mut collected = Vec::new();
+        self.collect_into(name, include_private, 1, &mut state, &mut collected)?;
+        Ok(collected)
+    }
+
+    fn collect_into(
+        &self,
+        name: &str,
+        include_private: bool,
+        depth: i32,
+        state: &mut TraversalState,
+        collected: &mut Vec<PcFile>,
+    ) -> Result<(), ParseError> {
+        if depth > self.max_depth {
+            return Err(ParseError::MaxDepthExceeded {
+                depth: self.max_depth,
+            });
+        }
+        // A package on the current path means the Requires: chain loops;
+        // one that is merely finished has already been collected.
+        match state.enter(name) {
+            Err(cycle) => return Err(ParseError::CircularDependency { cycle }),
+            Ok(false) => return Ok(()),
+            Ok(true) => {}
+        }
+        let pc = self.load_package(name)?;
+        let mut deps = self.dependency_names(&pc, Keyword::Requires)?;
+        if include_private {
+            deps.extend(self.dependency_names(&pc, Keyword::RequiresPrivate)?);
+        }
+        collected.push(pc);
+        for dep in deps {
+            self.audit(AuditEvent::DependencyTraversal {
+                from: name.to_owned(),
+                to: dep.clone(),
+                depth: depth as u32,
+            });
+            self.collect_into(&dep, include_private, depth + 1, state, collected)?;
+        }
+        state.leave(name);
+        Ok(())
+    }
+
+    /// The package names listed in a `Requires:`-style field of `pc`.
+    fn dependency_names(&self, pc: &PcFile, keyword: Keyword) -> Result<Vec<String>, ParseError> {
+        Ok(self
+            .resolve_field(pc, keyword)?
+            .map(|field| crate::pkg::dependency_names(&field))
+            .unwrap_or_default())
+    }
+
+    /// Expands `keyword` in `pc` with this client's global variable
+    /// overrides shadowing the file-local definitions.
+    fn resolve_field(&self, pc: &PcFile, keyword: Keyword) -> Result<Option<String>, ParseError> {
+        let options = ResolveOptions {
+            global_vars: self.global_vars.clone(),
+            ..ResolveOptions::default()
+        };
+        pc.resolve_field_with_options(keyword, &options)
+    }
+
+    /// Collects `keywords` across `pc` and its transitive requirements
+    /// into one deduplicated fragment list.
+    fn collect_fragments(
+        &self,
+        name: &str,
+        keywords: &[Keyword],
+        include_private: bool,
+    ) -> Result<FragmentList, ParseError> {
+        let mut merged = FragmentList::new();
+        for pc in self.collect_transitive(name, include_private)? {
+            for &keyword in keywords {
+                if let Some(field) = self.resolve_field(&pc, keyword)? {
+                    merged = merged.merge(FragmentList::parse(&field)?);
+                }
+            }
+        }
+        Ok(merged)
+    }
+
+    fn as_strs(dirs: &[String]) -> Vec<&str> {
+        dirs.iter().map(String::as_str).collect()
+    }
+
+    /// The compile flags for `name` and everything it requires.
+    ///
+    /// `Cflags:` fields are collected across the transitive `Requires:`
+    /// closure — plus `Requires.private:` when static linking is enabled —
+    /// merged with duplicates collapsed, and stripped of `-I` flags that
+    /// point into the configured system include directories (unless system
+    /// cflags are allowed).
+    pub fn cflags_for(&self, name: &str) -> Result<FragmentList, ParseError> {
+        self.cflags_for_mode(name, self.default_mode())
+    }
+
+    /// The link flags for `name` and everything it requires.
+    ///
+    /// `Libs:` fields are collected across the transitive `Requires:`
+    /// closure — plus `Requires.private:` and `Libs.private:` when static
+    /// linking is enabled — and stripped of `-L` flags that point into the
+    /// configured system library directories (unless system libs are
+    /// allowed).
+    pub fn libs_for(&self, name: &str) -> Result<FragmentList, ParseError> {
+        self.libs_for_mode(name, self.default_mode())
+    }
+
+    /// Shorthand for [`Client::cflags_for_mode`] in [`StaticMode::Static`],
+    /// regardless of how the client itself is configured.
+    pub fn static_cflags_for(&self, name: &str) -> Result<FragmentList, ParseError> {
+        self.cflags_for_mode(name, StaticMode::Static)
+    }
+
+    /// Shorthand for [`Client::libs_for_mode`] in [`StaticMode::Static`],
+    /// regardless of how the client itself is configured.
+    pub fn static_libs_for(&self, name: &str) -> Result<FragmentList, ParseError> {
+        self.libs_for_mode(name, StaticMode::Static)
+    }
+
+    /// Like [`Client::cflags_for`], with the linking mode passed explicitly
+    /// instead of taken from [`Client::set_static`].
+    pub fn cflags_for_mode(
+        &self,
+        name: &str,
+        mode: StaticMode,
+    ) -> Result<FragmentList, ParseError> {
+        let merged =
+            self.collect_fragments(name, &[Keyword::Cflags], mode == StaticMode::Static)?;
+        Ok(self.filter_cflags(merged))
+    }
+
+    /// Like [`Client::libs_for`], with the linking mode passed explicitly
+    /// instead of taken from [`Client::set_static`].
+    pub fn libs_for_mode(&self, name: &str, mode: StaticMode) -> Result<FragmentList, ParseError> {
+        let keywords: &[Keyword] = match mode {
+            StaticMode::Static => &[Keyword::Libs, Keyword::LibsPrivate],
+            StaticMode::Dynamic => &[Keyword::Libs],
+        };
+        let merged = self.collect_fragments(name, keywords, mode == StaticMode::Static)?;
+        Ok(self.filter_libs(merged))
+    }
+
+    /// The mode implied by the [`Client::set_static`] flag.
+    fn default_mode(&self) -> StaticMode {
+        if self.static_linking {
+            StaticMode::Static
+        } else {
+            StaticMode::Dynamic
+        }
+    }
+
+    /// Strips `-I` flags that point into the configured system include
+    /// directories, unless system cflags are allowed.
+    ///
+    /// Only the client's own [`Client::allow_system_cflags`] flag decides;
+    /// the environment was read once in [`Client::from_env`], so a
+    /// programmatically configured client filters regardless of what
+    /// `PKG_CONFIG_ALLOW_SYSTEM_CFLAGS` says at call time.
+    pub fn filter_cflags(&self, fragments: FragmentList) -> FragmentList {
+        fragments.filter_system_paths_with(
+            self.allow_system_cflags,
+            self.allow_system_libs,
+            &Self::as_strs(&self.system_includedirs),
+            &[],
+            &[],
+            &[],
+        )
+    }
+
+    /// Strips `-L` flags that point into the configured system library
+    /// directories, unless system libs are allowed.
+    ///
+    /// Only the client's own [`Client::allow_system_libs`] flag decides;
+    /// the environment was read once in [`Client::from_env`], so a
+    /// programmatically configured client filters regardless of what
+    /// `PKG_CONFIG_ALLOW_SYSTEM_LIBS` says at call time.
+    pub fn filter_libs(&self, fragments: FragmentList) -> FragmentList {
+        fragments.filter_system_paths_with(
+            self.allow_system_cflags,
+            self.allow_system_libs,
+            &[],
+            &Self::as_strs(&self.system_libdirs),
+            &[],
+            &[],
+        )
+    }
+
+    /// Like [`Client::cflags_for`], rendered as a JSON object:
+    /// `{"flags": ["-I/opt/include", "-DFOO"]}`.
+    pub fn cflags_json(&self, name: &str) -> Result<String, ParseError> {
+        let flags = self.cflags_for(name)?;
+        Ok(format!("{{\"flags\": {}}}", json_array(&flags.to_vec())))
+    }
+
+    /// Like [`Client::libs_for`], rendered as a JSON object with the
+    /// fragments split by type:
+    /// `{"link_dirs": ["-L/opt/lib"], "libraries": ["-lfoo"], "other": []}`.
+    pub fn libs_json(&self, name: &str) -> Result<String, ParseError> {
+        let libs = self.libs_for(name)?;
+        let link_dirs = libs.filter_by_kind(FragmentType::LibraryPath).to_vec();
+        let libraries = libs.filter_by_kind(FragmentType::Library).to_vec();
+        let other: Vec<String> = libs
+            .iter()
+            .filter(|fragment| {
+                !matches!(
+                    fragment.kind(),
+                    FragmentType::LibraryPath | FragmentType::Library
+                )
+            })
+            .map(crate::fragment::Fragment::to_flag_string)
+            .collect();
+        Ok(format!(
+            "{{\"link_dirs\": {}, \"libraries\": {}, \"other\": {}}}",
+            json_array(&link_dirs),
+            json_array(&libraries),
+            json_array(&other)
+        ))
+    }
+
+    /// Renders a package's metadata as a JSON object: name, version,
+    /// description, dependencies and its own (unfiltered) flags.
+    pub fn package_info_json(&self, name: &str) -> Result<String, ParseError> {
+        let package = self.resolve_package(name, None)?;
+        let requires: Vec<String> = package
+            .requires
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Ok(format!(
+            "{{\"name\": {}, \"version\": {}, \"description\": {}, \
+             \"requires\": {}, \"cflags\": {}, \"libs\": {}}}",
+            json_string(&package.name),
+            json_string(&package.version),
+            json_string(&package.description),
+            json_array(&requires),
+            json_array(&package.cflags.to_vec()),
+            json_array(&package.libs.to_vec())
+        ))
+    }
+
+    /// Whether this implementation is at least as new as the pkg-config
+    /// release `required` names, as tested by
+    /// `pkg-config --atleast-pkgconfig-version`.
+    ///
+    /// Compares [`crate::PKGCONFIG_COMPAT_VERSION`], the upstream release
+    /// whose behaviour this crate tracks.
+    pub fn atleast_pkgconfig_version(&self, required: &str) -> bool {
+        crate::version::compare(crate::PKGCONFIG_COMPAT_VERSION, required).is_ge()
+    }
+
+    /// Whether `name` resolves and (optionally) satisfies `version_req`,
+    /// as tested by `pkg-config --exists`.
+    ///
+    /// The lookup goes through the package cache and does not load the
+    /// dependency graph. Every failure — not found, unparseable file,
+    /// version mismatch — answers `false`; [`Client::last_error`] then
+    /// tells the failures apart.
+    pub fn package_exists(&self, name: &str, version_req: Option<&str>) -> bool {
+        let result = self.check_package(name, version_req);
+        let exists = result.is_ok();
+        *self.last_error.lock().unwrap() = result.err();
+        exists
+    }
+
+    /// The fallible core of [`Client::package_exists`].
+    fn check_package(&self, name: &str, version_req: Option<&str>) -> crate::error::Result<()> {
+        let pc = self.load_package(name)?;
+        if let Some(required) = version_req {
+            let found = pc.version().unwrap_or_default();
+            if !crate::version::satisfies_range(found, required) {
+                return Err(PkgconfError::VersionMismatch {
+                    name: name.to_owned(),
+                    found: found.to_owned(),
+                    required: required.to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The `Version:` field of `name`, as printed by
+    /// `pkg-config --modversion`.
+    ///
+    /// Goes through the package cache and does not touch the dependency
+    /// graph, so a package with unresolvable requirements still reports
+    /// its version.
+    pub fn modversion(&self, name: &str) -> crate::error::Result<String> {
+        let pc = self.load_package(name)?;
+        Ok(pc.version().unwrap_or_default().to_owned())
+    }
+
+    /// The `Requires:` field of `name` as a formatted dependency list, as
+    /// printed by `pkg-config --print-requires`.
+    ///
+    /// The dependencies are displayed as declared — nothing is resolved
+    /// or version-checked.
+    pub fn print_requires(&self, name: &str) -> crate::error::Result<String> {
+        self.print_dependency_field(name, Keyword::Requires)
+    }
+
+    /// Like [`Client::print_requires`], for `Requires.private:`.
+    pub fn print_requires_private(&self, name: &str) -> crate::error::Result<String> {
+        self.print_dependency_field(name, Keyword::RequiresPrivate)
+    }
+
+    /// The shared body of the `--print-requires`-style queries.
+    fn print_dependency_field(
+        &self,
+        name: &str,
+        keyword: Keyword,
+    ) -> crate::error::Result<String> {
+        let pc = self.load_package(name)?;
+        let field = self.resolve_field(&pc, keyword)?.unwrap_or_default();
+        Ok(crate::dependency::DependencyList::parse(&field).to_string())
+    }
+
+    /// The error behind the most recent [`Client::package_exists`] `false`
+    /// answer, consumed on read. `Ok` answers clear it.
+    pub fn last_error(&self) -> Option<PkgconfError> {
+        self.last_error.lock().unwrap().take()
+    }
+
+    /// The fully-expanded value of one variable from `package`'s `.pc`
+    /// file, as printed by `pkg-config --variable=NAME`.
+    ///
+    /// Global variable overrides shadow the file-local definitions, and
+    /// when a sysroot is configured it is injected as `${pc_sysrootdir}`
+    /// before expansion. Asking for a variable the package never defines
+    /// fails with [`PkgconfError::UndefinedVariable`].
+    pub fn print_variable(&self, package: &str, variable: &str) -> crate::error::Result<String> {
+        let mut pc = self.load_package(package)?;
+        if let Some(sysroot) = &self.sysroot_dir {
+            pc.set_sysroot_dir(Some(sysroot));
+        }
+        let overrides: Vec<(&str, &str)> = self
+            .global_vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let vars = pc.resolve_variables_with_overrides(&overrides)?;
+        vars.get(variable)
+            .cloned()
+            .ok_or_else(|| PkgconfError::UndefinedVariable(variable.to_owned()))
+    }
+
+    /// Resolves `pc`'s variables, rewriting `prefix` to be relative to the
+    /// `.pc` file's own location when path relocation is enabled.
+    ///
+    /// Relocation follows the conventional `<prefix>/lib/pkgconfig/foo.pc`
+    /// layout: the new prefix is the grandparent of the directory holding
+    /// `pc_path`, so a tree copied wholesale keeps working. It is enabled by
+    /// `PKG_CONFIG_RELOCATE_PATHS` (and vetoed by
+    /// `PKG_CONFIG_DONT_RELOCATE_PATHS`) in [`Client::from_env`]; when
+    /// disabled, this is plain variable resolution.
+    pub fn apply_relocate(&self, pc: &PcFile, pc_path: &Path) -> HashMap<String, String> {
+        let mut vars = pc.resolve_variables().unwrap_or_default();
+        if !self.relocate_paths {
+            return vars;
+        }
+        let prefix = pc_path.parent().and_then(Path::parent).and_then(Path::parent);
+        if let Some(prefix) = prefix {
+            vars.insert("prefix".to_owned(), prefix.display().to_string());
+        }
+        vars
+    }
+
+    /// Enumerates every package visible in the search path as
+    /// `(name, version)` pairs, sorted by name.
+    ///
+    /// A package in a higher-priority directory shadows one with the same
+    /// file stem further down the path, matching lookup behaviour.
+    /// `-uninstalled` variants are omitted, directories that cannot be
+    /// read are skipped, and unparseable `.pc` files produce a warning on
+    /// stderr rather than failing the whole scan.
+    pub fn list_all_packages(&self) -> Result<Vec<(String, String)>, ParseError> {
+        #[cfg(feature = "parallel")]
+        {
+            self.list_all_parallel()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            self.list_all_sequential()
+        }
+    }
+
+    /// The single-threaded scan behind [`Client::list_all_packages`].
+    pub(crate) fn list_all_sequential(&self) -> Result<Vec<(String, String)>, ParseError> {
+        let mut seen: HashMap<String, (String, String)> = HashMap::new();
+        for (stem, path) in self.scan_candidates() {
+            if seen.contains_key(&stem) {
+                continue;
+            }
+            if let Some(entry) = Self::scan_entry(&stem, &path) {
+                seen.insert(stem, entry);
+            }
+        }
+        let mut packages: Vec<(String, String)> = seen.into_values().collect();
+        packages.sort();
+        Ok(packages)
+    }
+
+    /// The rayon-backed scan behind [`Client::list_all_packages`]: files are
+    /// parsed in parallel, then merged sequentially so the shadowing rules
+    /// and output order match the single-threaded path exactly.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn list_all_parallel(&self) -> Result<Vec<(String, String)>, ParseError> {
+        use rayon::prelude::*;
+
+        let candidates = self.scan_candidates();
+        let parsed: Vec<(String, Option<(String, String)>)> = candidates
+            .into_par_iter()
+            .map(|(stem, path)| {
+                let entry = Self::scan_entry(&stem, &path);
+                (stem, entry)
+            })
+            .collect();
+        let mut seen: HashMap<String, (String, String)> = HashMap::new();
+        for (stem, entry) in parsed {
+            if seen.contains_key(&stem) {
+                continue;
+            }
+            if let Some(entry) = entry {
+                seen.insert(stem, entry);
+            }
+        }
+        let mut packages: Vec<(String, String)> = seen.into_values().collect();
+        packages.sort();
+        Ok(packages)
+    }
+
+    /// Resolves every name in `names` against the shared cache, returning
+    /// results index-aligned with the input so callers can zip them.
+    ///
+    /// One package failing to resolve does not disturb the rest of the
+    /// batch. With the `parallel` feature the roots are resolved
+    /// concurrently on the rayon pool.
+    pub fn batch_resolve(&self, names: &[&str]) -> Vec<Result<Package, ParseError>> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            names
+                .par_iter()
+                .map(|name| self.resolve_package(name, None))
+                .collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            names
+                .iter()
+                .map(|name| self.resolve_package(name, None))
+                .collect()
+        }
+    }
+
+    /// The compile flags of every package in `names` and their transitive
+    /// dependencies, merged into one list with duplicates collapsed.
+    pub fn batch_cflags(&self, names: &[&str]) -> Result<FragmentList, ParseError> {
+        let mut merged = FragmentList::new();
+        for name in names {
+            merged = merged.merge(self.collect_fragments(
+                name,
+                &[Keyword::Cflags],
+                self.default_mode() == StaticMode::Static,
+            )?);
+        }
+        Ok(self.filter_cflags(merged))
+    }
+
+    /// The names of every package in the search path whose `.pc` file stem
+    /// matches `pattern`, sorted and deduplicated.
+    ///
+    /// The pattern is a simple glob: `*` matches any run of characters
+    /// (including none) and `?` matches exactly one; everything else is
+    /// literal. `gtk*` finds `gtk-2.0` and `gtk-3.0`, `*-2.0` finds every
+    /// 2.0-era module.
+    pub fn find_packages_matching(&self, pattern: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .scan_candidates()
+            .into_iter()
+            .map(|(stem, _)| stem)
+            .filter(|stem| glob_matches(pattern, stem))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Enumerates the `.pc` files a full scan would consider, in search-path
+    /// priority order, skipping `-uninstalled` variants and unreadable
+    /// directories.
+    fn scan_candidates(&self) -> Vec<(String, PathBuf)> {
+        let mut candidates = Vec::new();
+        for dir in &self.search_paths {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "pc") {
+                    continue;
+                }
+                let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                if stem.ends_with("-uninstalled") {
+                    continue;
+                }
+                candidates.push((stem.to_owned(), path));
+            }
+        }
+        candidates
+    }
+
+    /// Parses one scan candidate into its `(name, version)` entry, warning
+    /// on stderr and answering `None` for unparseable files.
+    fn scan_entry(stem: &str, path: &Path) -> Option<(String, String)> {
+        match PcFile::from_path(path) {
+            Ok(pc) => Some((
+                pc.name().unwrap_or(stem).to_owned(),
+                pc.version().unwrap_or_default().to_owned(),
+            )),
+            Err(err) => {
+                eprintln!("warning: skipping {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Resolves `name` to a [`Package`], optionally validating its version.
+    ///
+    /// The lookup goes through the package cache. When no `.pc` file
+    /// matches the name directly, the search path is scanned for a package
+    /// whose `Provides:` field declares it. `version_req` is a
+    /// comma-separated list of constraints (`>= 1.2, < 2.0`); a located
+    /// package whose version fails it is reported as
+    /// [`ParseError::VersionMismatch`].
+    pub fn resolve_package(
+        &self,
+        name: &str,
+        version_req: Option<&str>,
+    ) -> Result<Package, ParseError> {
+        let pc = match self.load_package(name) {
+            Ok(pc) => pc,
+            Err(ParseError::PackageNotFound { .. }) => self.find_provider(name)?,
+            Err(err) => return Err(err),
+        };
+        if let Some(required) = version_req {
+            let found = pc.version().unwrap_or_default();
+            let passed = crate::version::satisfies_range(found, required);
+            self.audit(AuditEvent::VersionCheck {
+                name: name.to_owned(),
+                found: found.to_owned(),
+                required: required.to_owned(),
+                passed,
+            });
+            if !passed {
+                return Err(ParseError::VersionMismatch {
+                    name: name.to_owned(),
+                    found: found.to_owned(),
+                    required: required.to_owned(),
+                });
+            }
+        }
+        Package::from_pc(&pc, &self.global_vars)
+    }
+
+    /// Scans the search path for a package whose `Provides:` field
+    /// declares `name`.
+    fn find_provider(&self, name: &str) -> Result<PcFile, ParseError> {
+        for dir in &self.search_paths {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|ext| ext != "pc") {
+                    continue;
+                }
+                let Ok(pc) = PcFile::from_path(&path) else {
+                    continue;
+                };
+                let provides = pc
+                    .get_provides()
+                    .is_some_and(|list| list.iter().any(|dep| dep.name == name));
+                if provides {
+                    return Ok(pc);
+                }
+            }
+        }
+        Err(ParseError::PackageNotFound {
+            name: name.to_owned(),
+        })
+    }
+
+    /// Appends a directory to the search path, below everything already
+    /// configured. The path is canonicalised so symlinked directories
+    /// compare and resolve consistently.
+    pub fn add_search_dir(&mut self, path: &Path) {
+        self.search_paths.push(Self::canonical(path));
+    }
+
+    /// Inserts a directory at the front of the search path, shadowing
+    /// everything already configured.
+    pub fn prepend_search_dir(&mut self, path: &Path) {
+        self.search_paths.insert(0, Self::canonical(path));
+    }
+
+    /// Replaces the search path wholesale with `dirs`, in order.
+    pub fn set_search_dirs(&mut self, dirs: &[&Path]) {
+        self.search_paths = dirs.iter().map(|dir| Self::canonical(dir)).collect();
+    }
+
+    /// Canonicalises `path`, falling back to the path as given when it
+    /// does not (yet) exist.
+    fn canonical(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Overrides a variable in every `.pc` file this client loads, like
+    /// `pkg-config --define-variable=name=value`. The override shadows
+    /// the file-local definition wherever the variable is referenced.
+    pub fn set_global_var(&mut self, name: &str, value: &str) {
+        self.global_vars
+            .insert(name.to_owned(), value.to_owned());
+    }
+
+    /// Drops all variable overrides set via [`Client::set_global_var`].
+    pub fn clear_global_vars(&mut self) {
+        self.global_vars.clear();
+    }
+
+    /// Enables or disables static linking mode, which folds the
+    /// `.private` variants of `Requires:` and `Libs:` into link output.
+    pub fn set_static(&mut self, static_linking: bool) {
+        self.static_linking = static_linking;
+    }
+
+    /// The directories searched for `.pc` files, highest priority first.
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// The configured sysroot, when cross-compiling.
+    pub fn sysroot_dir(&self) -> Option<&std::path::Path> {
+        self.sysroot_dir.as_deref()
+    }
+
+    /// The `--define-variable`-style overrides applied to every lookup.
+    pub fn global_vars(&self) -> &HashMap<String, String> {
+        &self.global_vars
+    }
+
+    /// The header directories treated as system paths.
+    pub fn system_includedirs(&self) -> &[String] {
+        &self.system_includedirs
+    }
+
+    /// The library directories treated as system paths.
+    pub fn system_libdirs(&self) -> &[String] {
+        &self.system_libdirs
+    }
+
+    /// Whether `-I`/`-F` flags pointing at system directories are kept.
+    pub fn allow_system_cflags(&self) -> bool {
+        self.allow_system_cflags
+    }
+
+    /// Whether `-L` flags pointing at system directories are kept.
+    pub fn allow_system_libs(&self) -> bool {
+        self.allow_system_libs
+    }
+
+    /// Whether `Libs.private` is folded into link output.
+    pub fn is_static(&self) -> bool {
+        self.static_linking
+    }
+
+    /// Whether `-uninstalled` package variants are ignored.
+    pub fn disable_uninstalled(&self) -> bool {
+        self.disable_uninstalled
+    }
+
+    /// Whether [`Client::apply_relocate`] rewrites `prefix` relative to
+    /// each `.pc` file's location.
+    pub fn relocate_paths(&self) -> bool {
+        self.relocate_paths
+    }
+}
+
+/// Escapes `value` as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Renders `items` as a JSON array of strings.
+fn json_array(items: &[String]) -> String {
+    let items: Vec<String> = items.iter().map(|item| json_string(item)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Matches `name` against a glob `pattern` where `*` matches any run of
+/// characters and `?` matches exactly one.
+///
+/// Iterative with single-star backtracking, so a scan over thousands of
+/// stems costs no allocation.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                // Tentatively match nothing; retry from here matching one
+                // more character each time the suffix fails.
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(&literal) if literal == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, consumed)) => {
+                    backtrack = Some((star, consumed + 1));
+                    p = star + 1;
+                    n = consumed + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_PKGCONFIG_PATH;
+    // The environment is process-global, so these tests share one lock
+    // with every other module that touches `PKG_CONFIG_*` variables.
+    use crate::ENV_LOCK;
+    use std::path::Path;
+
+    #[test]
+    fn default_client_uses_the_stock_search_path() {
+        let client = Client::new();
+        let paths: Vec<&Path> = client.search_paths().iter().map(PathBuf::as_path).collect();
+        let expected: Vec<&Path> = DEFAULT_PKGCONFIG_PATH.iter().map(Path::new).collect();
+        assert_eq!(paths, expected);
+        assert!(client.sysroot_dir().is_none());
+        assert!(!client.is_static());
+    }
+
+    /// Creates a unique scratch directory under the system temp dir.
+    fn scratch_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-client-{label}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_pc(dir: &Path, name: &str, version: &str) {
+        std::fs::write(
+            dir.join(format!("{name}.pc")),
+            format!("Name: {name}
+Version: {version}
+Description: d
+"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn find_package_respects_search_path_priority() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let high = scratch_dir("high");
+        let low = scratch_dir("low");
+        write_pc(&high, "foo", "2.0");
+        write_pc(&low, "foo", "1.0");
+        write_pc(&low, "bar", "1.0");
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe {
+            std::env::set_var(
+                "PKG_CONFIG_LIBDIR",
+                std::env::join_paths([&high, &low]).unwrap(),
+            );
+        }
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        assert_eq!(client.find_package("foo").unwrap().version(), Some("2.0"));
+        assert_eq!(client.find_package("bar").unwrap().version(), Some("1.0"));
+        let err = client.find_package("missing").unwrap_err();
+        assert!(matches!(err, ParseError::PackageNotFound { name } if name == "missing"));
+    }
+
+    #[test]
+    fn uninstalled_variant_is_preferred_unless_disabled() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("uninstalled");
+        write_pc(&dir, "foo", "1.0");
+        write_pc(&dir, "foo-uninstalled", "1.0-dev");
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let client = Client::from_env();
+        assert_eq!(
+            client.find_package("foo").unwrap().name(),
+            Some("foo-uninstalled")
+        );
+        assert!(client.resolve_package("foo", None).unwrap().is_uninstalled());
+        unsafe { std::env::set_var("PKG_CONFIG_DISABLE_UNINSTALLED", "1") };
+        let client = Client::from_env();
+        unsafe {
+            std::env::remove_var("PKG_CONFIG_LIBDIR");
+            std::env::remove_var("PKG_CONFIG_DISABLE_UNINSTALLED");
+        }
+        assert_eq!(client.find_package("foo").unwrap().name(), Some("foo"));
+        assert!(!client.resolve_package("foo", None).unwrap().is_uninstalled());
+    }
+
+    #[test]
+    fn cflags_and_libs_cover_the_transitive_closure() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("transitive");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 1.0\nDescription: d\nRequires: bar\n\
+             Cflags: -I/opt/foo/include -I/usr/include\nLibs: -L/opt/foo/lib -lfoo\n\
+             Libs.private: -lfoo-extra\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("bar.pc"),
+            "Name: bar\nVersion: 1.0\nDescription: d\n\
+             Cflags: -I/opt/bar/include\nLibs: -L/usr/lib -lbar\n",
+        )
+        .unwrap();
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let mut client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        assert_eq!(
+            client.cflags_for("foo").unwrap().render(' '),
+            "-I/opt/foo/include -I/opt/bar/include"
+        );
+        assert_eq!(
+            client.libs_for("foo").unwrap().render(' '),
+            "-L/opt/foo/lib -lfoo -lbar"
+        );
+        client.set_static(true);
+        assert_eq!(
+            client.libs_for("foo").unwrap().render(' '),
+            "-L/opt/foo/lib -lfoo -lfoo-extra -lbar"
+        );
+    }
+
+    #[test]
+    fn load_package_hits_the_cache_after_the_file_is_gone() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("cache");
+        write_pc(&dir, "foo", "1.0");
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        client.cflags_for("foo").unwrap();
+        std::fs::remove_file(dir.join("foo.pc")).unwrap();
+        // The second resolution is served from the cache.
+        assert!(client.cflags_for("foo").is_ok());
+        assert!(matches!(
+            client.find_package("foo"),
+            Err(ParseError::PackageNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn list_all_packages_shadows_and_sorts() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let high = scratch_dir("list-high");
+        let low = scratch_dir("list-low");
+        write_pc(&high, "zlib", "1.3");
+        write_pc(&low, "zlib", "1.2");
+        write_pc(&low, "alpha", "0.1");
+        write_pc(&low, "alpha-uninstalled", "0.1");
+        std::fs::write(low.join("broken.pc"), "Name broken no colon\n").unwrap();
+        unsafe {
+            std::env::set_var(
+                "PKG_CONFIG_LIBDIR",
+                std::env::join_paths([&high, &low]).unwrap(),
+            );
+        }
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        let packages = client.list_all_packages().unwrap();
+        assert_eq!(
+            packages,
+            vec![
+                ("alpha".to_owned(), "0.1".to_owned()),
+                ("zlib".to_owned(), "1.3".to_owned()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_scan_matches_the_sequential_scan() {
+        let high = scratch_dir("par-high");
+        let low = scratch_dir("par-low");
+        write_pc(&high, "zlib", "1.3");
+        write_pc(&low, "zlib", "1.2");
+        write_pc(&low, "alpha", "0.1");
+        write_pc(&low, "alpha-uninstalled", "0.1");
+        std::fs::write(low.join("broken.pc"), "Name broken no colon\n").unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&high, &low]);
+        assert_eq!(
+            client.list_all_parallel().unwrap(),
+            client.list_all_sequential().unwrap()
+        );
+    }
+
+    #[test]
+    fn global_vars_override_file_local_variables() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("globals");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "prefix=/usr\nincludedir=${prefix}/include\n\
+             Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I${includedir}/foo\n",
+        )
+        .unwrap();
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let mut client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        client.set_global_var("prefix", "/opt/stage");
+        assert_eq!(
+            client.cflags_for("foo").unwrap().render(' '),
+            "-I/opt/stage/include/foo"
+        );
+        client.clear_global_vars();
+        assert!(client.global_vars().is_empty());
+        assert_eq!(
+            client.cflags_for("foo").unwrap().render(' '),
+            "-I/usr/include/foo"
+        );
+    }
+
+    #[test]
+    fn search_dir_mutators_control_priority() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let base = scratch_dir("dirs-base");
+        let custom = scratch_dir("dirs-custom");
+        write_pc(&base, "foo", "1.0");
+        write_pc(&custom, "foo", "2.0");
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &base) };
+        let mut client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        // An appended directory loses to the configured path...
+        client.add_search_dir(&custom);
+        assert_eq!(client.find_package("foo").unwrap().version(), Some("1.0"));
+        // ...while a prepended one shadows it.
+        client.prepend_search_dir(&custom);
+        assert_eq!(client.find_package("foo").unwrap().version(), Some("2.0"));
+        client.set_search_dirs(&[&custom]);
+        assert_eq!(client.search_paths().len(), 1);
+        assert_eq!(client.find_package("foo").unwrap().version(), Some("2.0"));
+    }
+
+    #[test]
+    fn resolve_package_validates_the_version_requirement() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let old = scratch_dir("resolve-old");
+        let new = scratch_dir("resolve-new");
+        write_pc(&old, "foo", "1.4");
+        write_pc(&new, "foo", "2.1");
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &old) };
+        let mut client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        client.prepend_search_dir(&new);
+        let package = client.resolve_package("foo", Some(">= 2.0, < 3.0")).unwrap();
+        assert_eq!(package.pc().version(), Some("2.1"));
+        let err = client.resolve_package("foo", Some(">= 3.0")).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::VersionMismatch { name, found, required }
+                if name == "foo" && found == "2.1" && required == ">= 3.0"
+        ));
+    }
+
+    #[test]
+    fn resolve_package_falls_back_to_provides() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("resolve-provides");
+        std::fs::write(
+            dir.join("openssl.pc"),
+            "Name: openssl\nVersion: 3.0\nDescription: d\nProvides: virtual-ssl = 3.0\n",
+        )
+        .unwrap();
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        let package = client.resolve_package("virtual-ssl", None).unwrap();
+        assert_eq!(package.id(), "openssl");
+        assert!(matches!(
+            client.resolve_package("virtual-tls", None),
+            Err(ParseError::PackageNotFound { name }) if name == "virtual-tls"
+        ));
+    }
+
+    #[test]
+    fn traversal_depth_limit_is_enforced() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("depth");
+        for i in 0..10 {
+            let requires = if i < 9 {
+                format!("Requires: link{}\n", i + 1)
+            } else {
+                String::new()
+            };
+            std::fs::write(
+                dir.join(format!("link{i}.pc")),
+                format!("Name: link{i}\nVersion: 1.0\nDescription: d\n{requires}"),
+            )
+            .unwrap();
+        }
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let client = Client::from_env().with_max_depth(5);
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        assert!(matches!(
+            client.cflags_for("link0"),
+            Err(ParseError::MaxDepthExceeded { depth: 5 })
+        ));
+        // The full chain fits under the default limit.
+        let client = client.with_max_depth(DEFAULT_MAX_TRAVERSAL_DEPTH);
+        assert!(client.cflags_for("link0").is_ok());
+    }
+
+    #[test]
+    fn from_env_honours_the_traverse_depth_variable() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe { std::env::set_var("PKG_CONFIG_MAXIMUM_TRAVERSE_DEPTH", "7") };
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_MAXIMUM_TRAVERSE_DEPTH") };
+        assert_eq!(client.max_depth, 7);
+        assert_eq!(Client::new().max_depth, DEFAULT_MAX_TRAVERSAL_DEPTH);
+    }
+
+    #[test]
+    fn repeated_lookups_are_cache_hits() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let dir = scratch_dir("stats");
+        write_pc(&dir, "foo", "1.0");
+        unsafe { std::env::set_var("PKG_CONFIG_LIBDIR", &dir) };
+        let client = Client::from_env();
+        unsafe { std::env::remove_var("PKG_CONFIG_LIBDIR") };
+        client.cflags_for("foo").unwrap();
+        client.libs_for("foo").unwrap();
+        let stats = client.cache().lock().unwrap().stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+    }
+
+    #[test]
+    fn circular_requires_chain_is_an_error() {
+        let dir = scratch_dir("cycle");
+        std::fs::write(
+            dir.join("a.pc"),
+            "Name: a\nVersion: 1.0\nDescription: d\nRequires: b\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("b.pc"),
+            "Name: b\nVersion: 1.0\nDescription: d\nRequires: a\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert!(matches!(
+            client.cflags_for("a"),
+            Err(ParseError::CircularDependency { cycle }) if cycle == ["a", "b"]
+        ));
+        assert!(matches!(
+            client.libs_for("b"),
+            Err(ParseError::CircularDependency { cycle }) if cycle == ["b", "a"]
+        ));
+    }
+
+    #[test]
+    fn print_variable_expands_with_and_without_sysroot() {
+        let dir = scratch_dir("variable");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "prefix=${pc_sysrootdir}/usr\nexec_prefix=${prefix}\n\n\
+             Name: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(client.print_variable("foo", "prefix").unwrap(), "/usr");
+        assert_eq!(client.print_variable("foo", "exec_prefix").unwrap(), "/usr");
+
+        let mut sysrooted = Client::new().with_personality(Personality {
+            sysroot_dir: Some(PathBuf::from("/sr")),
+            ..Default::default()
+        });
+        sysrooted.set_search_dirs(&[&dir]);
+        assert_eq!(sysrooted.print_variable("foo", "prefix").unwrap(), "/sr/usr");
+    }
+
+    #[test]
+    fn batch_resolve_keeps_input_order_through_failures() {
+        let dir = scratch_dir("batch-resolve");
+        write_pc(&dir, "alpha", "1.0");
+        write_pc(&dir, "beta", "2.0");
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        let results = client.batch_resolve(&["alpha", "missing", "beta"]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().version, "1.0");
+        assert!(matches!(
+            results[1],
+            Err(ParseError::PackageNotFound { ref name }) if name == "missing"
+        ));
+        assert_eq!(results[2].as_ref().unwrap().version, "2.0");
+    }
+
+    #[test]
+    fn batch_cflags_merges_and_deduplicates_across_roots() {
+        let dir = scratch_dir("batch-cflags");
+        for (name, cflags) in [
+            ("a", "-I/opt/shared/include -DA"),
+            ("b", "-I/opt/shared/include -DB"),
+            ("c", "-DC"),
+        ] {
+            std::fs::write(
+                dir.join(format!("{name}.pc")),
+                format!("Name: {name}\nVersion: 1.0\nDescription: d\nCflags: {cflags}\n"),
+            )
+            .unwrap();
+        }
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(
+            client.batch_cflags(&["a", "b", "c"]).unwrap().render(' '),
+            "-I/opt/shared/include -DA -DB -DC"
+        );
+    }
+
+    #[test]
+    fn find_packages_matching_filters_stems_with_globs() {
+        let dir = scratch_dir("glob");
+        for name in ["gtk-2.0", "gtk-3.0", "glib-2.0"] {
+            std::fs::write(
+                dir.join(format!("{name}.pc")),
+                format!("Name: {name}\nVersion: 1.0\nDescription: d\n"),
+            )
+            .unwrap();
+        }
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(client.find_packages_matching("gtk*"), ["gtk-2.0", "gtk-3.0"]);
+        assert_eq!(
+            client.find_packages_matching("*-2.0"),
+            ["glib-2.0", "gtk-2.0"]
+        );
+        assert_eq!(client.find_packages_matching("gtk-?.0"), ["gtk-2.0", "gtk-3.0"]);
+        assert_eq!(client.find_packages_matching("gtk-2.0"), ["gtk-2.0"]);
+        assert!(client.find_packages_matching("qt*").is_empty());
+    }
+
+    #[test]
+    fn glob_matcher_backtracks_and_handles_edge_cases() {
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("*", "anything"));
+        assert!(!glob_matches("?", ""));
+        assert!(glob_matches("a*b*c", "a-x-b-y-c"));
+        assert!(!glob_matches("a*b*c", "a-x-b-y"));
+        assert!(glob_matches("*.0", "gtk-2.0"));
+        assert!(!glob_matches("gtk", "gtk-2.0"));
+    }
+
+    #[test]
+    fn static_mode_pulls_in_private_dependencies() {
+        let dir = scratch_dir("static-mode");
+        std::fs::write(
+            dir.join("app.pc"),
+            "Name: app\nVersion: 1.0\nDescription: d\nRequires.private: secret\n\
+             Cflags: -I/opt/app/include\nLibs: -lapp\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("secret.pc"),
+            "Name: secret\nVersion: 1.0\nDescription: d\n\
+             Cflags: -I/opt/secret/include\nLibs: -lsecret\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(client.libs_for("app").unwrap().render(' '), "-lapp");
+        assert_eq!(
+            client.static_libs_for("app").unwrap().render(' '),
+            "-lapp -lsecret"
+        );
+        assert_eq!(
+            client.cflags_for("app").unwrap().render(' '),
+            "-I/opt/app/include"
+        );
+        assert_eq!(
+            client.static_cflags_for("app").unwrap().render(' '),
+            "-I/opt/app/include -I/opt/secret/include"
+        );
+        // The explicit mode wins over the client-wide static flag.
+        client.set_static(true);
+        assert_eq!(
+            client.libs_for_mode("app", StaticMode::Dynamic).unwrap().render(' '),
+            "-lapp"
+        );
+    }
+
+    #[test]
+    fn atleast_pkgconfig_version_compares_the_compat_version() {
+        let client = Client::new();
+        assert!(client.atleast_pkgconfig_version("0.28"));
+        assert!(client.atleast_pkgconfig_version(crate::PKGCONFIG_COMPAT_VERSION));
+        assert!(!client.atleast_pkgconfig_version("9999"));
+    }
+
+    #[test]
+    fn print_requires_lists_dependencies_as_declared() {
+        let dir = scratch_dir("print-requires");
+        std::fs::write(
+            dir.join("app.pc"),
+            "Name: app\nVersion: 1.0\nDescription: d\n\
+             Requires: foo >= 1.2, bar\nRequires.private: baz = 2.0\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        // Neither foo, bar nor baz exist in the search path; display-only
+        // queries must not care.
+        assert_eq!(client.print_requires("app").unwrap(), "foo >= 1.2, bar");
+        assert_eq!(client.print_requires_private("app").unwrap(), "baz = 2.0");
+        let err = client.print_requires("missing").unwrap_err();
+        assert!(matches!(err, PkgconfError::PackageNotFound(name) if name == "missing"));
+    }
+
+    #[test]
+    fn modversion_reports_the_version_field_verbatim() {
+        let dir = scratch_dir("modversion");
+        write_pc(&dir, "foo", "2.3.1");
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(client.modversion("foo").unwrap(), "2.3.1");
+        let err = client.modversion("missing").unwrap_err();
+        assert!(matches!(err, PkgconfError::PackageNotFound(name) if name == "missing"));
+    }
+
+    #[test]
+    fn package_exists_answers_without_loading_the_graph() {
+        let dir = scratch_dir("exists");
+        write_pc(&dir, "foo", "1.4");
+        // A dangling requirement must not matter for a bare existence check.
+        std::fs::write(
+            dir.join("broken-deps.pc"),
+            "Name: broken-deps\nVersion: 1.0\nDescription: d\nRequires: no-such-pkg\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert!(client.package_exists("foo", None));
+        assert!(client.last_error().is_none());
+        assert!(client.package_exists("broken-deps", None));
+        assert!(!client.package_exists("missing", None));
+        assert!(matches!(
+            client.last_error(),
+            Some(PkgconfError::PackageNotFound(name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn package_exists_checks_the_version_requirement() {
+        let dir = scratch_dir("exists-version");
+        write_pc(&dir, "foo", "1.4");
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert!(client.package_exists("foo", Some(">= 1.0")));
+        assert!(!client.package_exists("foo", Some(">= 2.0")));
+        assert!(matches!(
+            client.last_error(),
+            Some(PkgconfError::VersionMismatch { found, .. }) if found == "1.4"
+        ));
+    }
+
+    #[test]
+    fn last_error_distinguishes_corrupted_files_from_missing_ones() {
+        let dir = scratch_dir("exists-corrupt");
+        std::fs::write(dir.join("mangled.pc"), "Name: mangled\n!!! not a pc line\n").unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert!(!client.package_exists("mangled", None));
+        assert!(matches!(
+            client.last_error(),
+            Some(PkgconfError::Parse(ParseError::MalformedLine { .. }))
+        ));
+        // The error is consumed on read.
+        assert!(client.last_error().is_none());
+    }
+
+    #[test]
+    fn print_variable_honours_overrides_and_rejects_undefined() {
+        let dir = scratch_dir("variable-overrides");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "prefix=/usr\nlibdir=${prefix}/lib\n\n\
+             Name: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        client.set_global_var("prefix", "/opt/foo");
+        assert_eq!(client.print_variable("foo", "libdir").unwrap(), "/opt/foo/lib");
+        let err = client.print_variable("foo", "nope").unwrap_err();
+        assert!(matches!(err, PkgconfError::UndefinedVariable(name) if name == "nope"));
+    }
+
+    #[test]
+    fn apply_relocate_rewrites_prefix_relative_to_the_pc_file() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let root = scratch_dir("relocate");
+        let pkgconfig = root.join("lib/pkgconfig");
+        std::fs::create_dir_all(&pkgconfig).unwrap();
+        let pc_path = pkgconfig.join("foo.pc");
+        std::fs::write(
+            &pc_path,
+            "prefix=/opt/foo\nlibdir=${prefix}/lib\n\n\
+             Name: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let pc = PcFile::from_path(&pc_path).unwrap();
+
+        // Without the env var the variables resolve as authored.
+        let vars = Client::new().apply_relocate(&pc, &pc_path);
+        assert_eq!(vars.get("prefix").unwrap(), "/opt/foo");
+
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe { std::env::set_var("PKG_CONFIG_RELOCATE_PATHS", "1") };
+        let client = Client::from_env();
+        assert!(client.relocate_paths());
+        let vars = client.apply_relocate(&pc, &pc_path);
+        assert_eq!(vars.get("prefix").unwrap(), &root.display().to_string());
+
+        unsafe { std::env::set_var("PKG_CONFIG_DONT_RELOCATE_PATHS", "1") };
+        let client = Client::from_env();
+        unsafe {
+            std::env::remove_var("PKG_CONFIG_RELOCATE_PATHS");
+            std::env::remove_var("PKG_CONFIG_DONT_RELOCATE_PATHS");
+        }
+        assert!(!client.relocate_paths());
+        let vars = client.apply_relocate(&pc, &pc_path);
+        assert_eq!(vars.get("prefix").unwrap(), "/opt/foo");
+    }
+
+    #[test]
+    fn with_personality_applies_cross_compile_settings() {
+        let personality = Personality {
+            triplet: "aarch64-linux-gnu".to_owned(),
+            sysroot_dir: Some(PathBuf::from("/sysroot")),
+            pkg_config_path: vec![PathBuf::from("/sysroot/usr/lib/pkgconfig")],
+            system_libdirs: vec![PathBuf::from("/sysroot/usr/lib")],
+            system_includedirs: vec![PathBuf::from("/sysroot/usr/include")],
+        };
+        let client = Client::new().with_personality(personality);
+        assert_eq!(
+            client.search_paths(),
+            [PathBuf::from("/sysroot/usr/lib/pkgconfig")]
+        );
+        assert_eq!(client.sysroot_dir(), Some(Path::new("/sysroot")));
+        assert_eq!(client.system_libdirs(), ["/sysroot/usr/lib"]);
+        assert_eq!(client.system_includedirs(), ["/sysroot/usr/include"]);
+        // An empty personality leaves the defaults in place.
+        let client = Client::new().with_personality(Default::default());
+        assert!(!client.search_paths().is_empty());
+    }
+
+    #[test]
+    fn audit_log_traces_lookups_and_traversal() {
+        let dir = scratch_dir("audit");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 1.0\nDescription: d\nRequires: bar\n",
+        )
+        .unwrap();
+        write_pc(&dir, "bar", "1.0");
+        let seen = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&seen);
+        let mut client = Client::new()
+            .with_audit(AuditLog::new_callback(move |event| {
+                sink.lock().unwrap().push(event)
+            }));
+        client.set_search_dirs(&[&dir]);
+        client.resolve_package("foo", Some(">= 1.0")).unwrap();
+        client.cflags_for("foo").unwrap();
+        let seen = seen.lock().unwrap();
+        assert!(seen.iter().any(|event| matches!(
+            event,
+            AuditEvent::PackageLookup { name, found: true, path: Some(_) } if name == "foo"
+        )));
+        assert!(seen.iter().any(|event| matches!(
+            event,
+            AuditEvent::VersionCheck { name, passed: true, .. } if name == "foo"
+        )));
+        assert!(seen.iter().any(|event| matches!(
+            event,
+            AuditEvent::DependencyTraversal { from, to, depth: 1 }
+                if from == "foo" && to == "bar"
+        )));
+    }
+
+    #[test]
+    fn json_output_is_well_formed() {
+        let dir = scratch_dir("json");
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 1.0\nDescription: a \"quoted\" one\n\
+             Cflags: -I/opt/include -DFOO\nLibs: -L/opt/lib -lfoo -pthread\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        assert_eq!(
+            client.cflags_json("foo").unwrap(),
+            r#"{"flags": ["-I/opt/include", "-DFOO"]}"#
+        );
+        assert_eq!(
+            client.libs_json("foo").unwrap(),
+            r#"{"link_dirs": ["-L/opt/lib"], "libraries": ["-lfoo"], "other": ["-pthread"]}"#
+        );
+        let info = client.package_info_json("foo").unwrap();
+        assert!(info.starts_with(r#"{"name": "foo", "version": "1.0""#));
+        assert!(info.contains(r#""description": "a \"quoted\" one""#));
+    }
+
+    #[test]
+    fn lint_warnings_are_reported_once_per_package() {
+        let dir = scratch_dir("warnings");
+        std::fs::write(
+            dir.join("sloppy.pc"),
+            "Name: sloppy\nVersion: unknown\nRequires: dep dep\n",
+        )
+        .unwrap();
+        write_pc(&dir, "dep", "1.0");
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        let _ = client.cflags_for("sloppy");
+        let _ = client.cflags_for("sloppy");
+        let warnings = client.drain_warnings();
+        let count = |warning: &Warning| warnings.iter().filter(|w| *w == warning).count();
+        assert_eq!(
+            count(&Warning::MissingDescription("sloppy".to_owned())),
+            1
+        );
+        assert_eq!(count(&Warning::SuspiciousVersion("sloppy".to_owned())), 1);
+        assert_eq!(
+            count(&Warning::DuplicateDependency {
+                package: "sloppy".to_owned(),
+                dependency: "dep".to_owned(),
+            }),
+            1
+        );
+        assert!(client.warnings().is_empty());
+    }
+
+    #[test]
+    fn from_env_reads_pkg_config_variables() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe {
+            std::env::set_var("PKG_CONFIG_PATH", "/opt/pc:/extra/pc");
+            std::env::set_var("PKG_CONFIG_LIBDIR", "/base/pc");
+            std::env::set_var("PKG_CONFIG_SYSROOT_DIR", "/sysroot");
+            std::env::set_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS", "1");
+        }
+        let client = Client::from_env();
+        unsafe {
+            std::env::remove_var("PKG_CONFIG_PATH");
+            std::env::remove_var("PKG_CONFIG_LIBDIR");
+            std::env::remove_var("PKG_CONFIG_SYSROOT_DIR");
+            std::env::remove_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS");
+        }
+        let paths: Vec<&Path> = client.search_paths().iter().map(PathBuf::as_path).collect();
+        assert_eq!(
+            paths,
+            [Path::new("/opt/pc"), Path::new("/extra/pc"), Path::new("/base/pc")]
+        );
+        assert_eq!(client.sysroot_dir(), Some(Path::new("/sysroot")));
+        assert!(client.allow_system_cflags());
+        assert!(!client.allow_system_libs());
+    }
+
+    #[test]
+    fn programmatic_client_filters_despite_ambient_allow_vars() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe {
+            std::env::set_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS", "1");
+            std::env::set_var("PKG_CONFIG_ALLOW_SYSTEM_LIBS", "1");
+        }
+        // Client::new never read the environment, so filtering stays on.
+        let client = Client::new();
+        let cflags = FragmentList::parse("-I/usr/include -I/opt/include").unwrap();
+        let libs = FragmentList::parse("-L/usr/lib -lfoo").unwrap();
+        let filtered_cflags = client.filter_cflags(cflags);
+        let filtered_libs = client.filter_libs(libs);
+        unsafe {
+            std::env::remove_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS");
+            std::env::remove_var("PKG_CONFIG_ALLOW_SYSTEM_LIBS");
+        }
+        assert_eq!(filtered_cflags.render(' '), "-I/opt/include");
+        assert_eq!(filtered_libs.render(' '), "-lfoo");
+    }
+}
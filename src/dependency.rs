@@ -0,0 +1,467 @@
+//! Parsed `Requires:`-style dependency lists.
+//!
+//! Fields like `Requires: openssl >= 1.1, zlib` name other packages with
+//! optional version constraints. [`DependencyList`] parses such a field
+//! into structured [`Dependency`] entries.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as HashMap;
+
+use crate::version;
+use crate::version::{Comparator, VersionReq};
+
+/// An error produced while parsing a dependency entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyError {
+    /// The input did not describe exactly one `name [op version]` entry.
+    Malformed {
+        /// The offending input.
+        input: String,
+    },
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyError::Malformed { input } => {
+                write!(f, "malformed dependency: {input:?}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for DependencyError {}
+
+/// A single package reference with an optional version constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// The name of the referenced package.
+    pub name: String,
+    /// The constraint operator, when a version requirement is present.
+    pub comparator: Option<Comparator>,
+    /// The version the constraint compares against, when present.
+    pub version: Option<String>,
+}
+
+impl Dependency {
+    /// Parses a single `name [op version]` entry.
+    ///
+    /// Fails when the input is empty, contains more than one entry, or has
+    /// a comparator with no version after it.
+    pub fn parse(s: &str) -> Result<Dependency, DependencyError> {
+        let malformed = || DependencyError::Malformed { input: s.to_owned() };
+        let list = DependencyList::parse(s);
+        if list.len() != 1 {
+            return Err(malformed());
+        }
+        let dep = list.deps.into_iter().next().unwrap();
+        if dep.comparator.is_some() && dep.version.is_none() {
+            return Err(malformed());
+        }
+        Ok(dep)
+    }
+
+    /// The version requirement as a [`VersionReq`], when one is present.
+    pub fn version_req(&self) -> Option<VersionReq> {
+        Some(VersionReq {
+            comparator: self.comparator?,
+            version: self.version.clone()?,
+        })
+    }
+
+    /// Whether `version` satisfies this dependency's constraint.
+    ///
+    /// A dependency without a version requirement is satisfied by any
+    /// version.
+    pub fn is_satisfied_by(&self, candidate: &str) -> bool {
+        match self.version_req() {
+            Some(req) => req.matches(candidate),
+            None => true,
+        }
+    }
+}
+
+impl fmt::Display for Dependency {
+    /// Writes the canonical `name` or `name op version` form.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let (Some(comparator), Some(version)) = (self.comparator, &self.version) {
+            write!(f, " {comparator} {version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Picks the stricter of two constraints on the same package.
+fn stricter(a: &Dependency, b: &Dependency) -> Dependency {
+    use Comparator::*;
+    let (Some(op_a), Some(version_a)) = (a.comparator, a.version.as_deref()) else {
+        return b.clone();
+    };
+    let (Some(op_b), Some(version_b)) = (b.comparator, b.version.as_deref()) else {
+        return a.clone();
+    };
+    let ordering = version::compare(version_a, version_b);
+    let a_wins = match (op_a, op_b) {
+        // An exact pin is as strict as a constraint gets.
+        (Equal, _) => true,
+        (_, Equal) => false,
+        // Lower bounds: the higher version is stricter; on a tie the
+        // exclusive bound is.
+        (Greater | GreaterOrEqual, Greater | GreaterOrEqual) => match ordering {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => op_a == Greater,
+        },
+        // Upper bounds: the lower version is stricter.
+        (Less | LessOrEqual, Less | LessOrEqual) => match ordering {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => op_a == Less,
+        },
+        // Mixed directions are not comparable; keep the left-hand side.
+        _ => true,
+    };
+    if a_wins { a.clone() } else { b.clone() }
+}
+
+/// An ordered list of [`Dependency`] entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyList {
+    deps: Vec<Dependency>,
+}
+
+impl DependencyList {
+    /// Creates an empty list.
+    pub fn new() -> DependencyList {
+        DependencyList::default()
+    }
+
+    /// Parses a `Requires:`-style field.
+    ///
+    /// Entries are separated by commas and/or whitespace; a comparator
+    /// token (`>=`, `=`, …) binds the following token to the preceding
+    /// package name as its version constraint.
+    pub fn parse(field: &str) -> DependencyList {
+        let tokens: Vec<&str> = field
+            .split([',', ' ', '\t'])
+            .filter(|token| !token.is_empty())
+            .collect();
+        let mut deps = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let name = tokens[i].to_owned();
+            i += 1;
+            let mut comparator = None;
+            let mut version = None;
+            if i < tokens.len()
+                && let Some(op) = Comparator::parse(tokens[i])
+            {
+                comparator = Some(op);
+                i += 1;
+                if i < tokens.len() {
+                    version = Some(tokens[i].to_owned());
+                    i += 1;
+                }
+            }
+            deps.push(Dependency {
+                name,
+                comparator,
+                version,
+            });
+        }
+        DependencyList { deps }
+    }
+
+    /// Builds a list of bare package names, as if parsing `"a, b, c"`.
+    pub fn from_packages(names: &[&str]) -> DependencyList {
+        DependencyList {
+            deps: names
+                .iter()
+                .map(|name| Dependency {
+                    name: (*name).to_owned(),
+                    comparator: None,
+                    version: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Appends a dependency to the list.
+    pub fn push(&mut self, dep: Dependency) {
+        self.deps.push(dep);
+    }
+
+    /// Combines two lists, deduplicating by package name. When both sides
+    /// constrain the same package, the stricter constraint is kept: the
+    /// higher lower bound, the lower upper bound, and an exact `=` pin over
+    /// anything else. Constraints in incomparable directions keep the
+    /// left-hand side's entry.
+    pub fn merge(&self, other: &DependencyList) -> DependencyList {
+        let mut merged = self.clone();
+        for dep in &other.deps {
+            match merged.deps.iter_mut().find(|existing| existing.name == dep.name) {
+                Some(existing) => *existing = stricter(existing, dep),
+                None => merged.deps.push(dep.clone()),
+            }
+        }
+        merged
+    }
+
+    /// Looks up a dependency by package name.
+    pub fn get(&self, name: &str) -> Option<&Dependency> {
+        self.deps.iter().find(|dep| dep.name == name)
+    }
+
+    /// Iterates over the dependencies in order.
+    pub fn iter(&self) -> core::slice::Iter<'_, Dependency> {
+        self.deps.iter()
+    }
+
+    /// The number of dependencies in the list.
+    pub fn len(&self) -> usize {
+        self.deps.len()
+    }
+
+    /// Whether the list contains no dependencies.
+    pub fn is_empty(&self) -> bool {
+        self.deps.is_empty()
+    }
+
+    /// Checks every entry against a pre-loaded `{name -> version}` package
+    /// set and returns the ones it fails to satisfy.
+    ///
+    /// An empty result means the whole list is satisfied. This answers a
+    /// batch of dependency checks without a search-path lookup per entry,
+    /// for callers that already know what is installed.
+    pub fn satisfies_all(&self, packages: &HashMap<&str, &str>) -> Vec<UnsatisfiedDep> {
+        let mut unsatisfied = Vec::new();
+        for dep in &self.deps {
+            let found = packages.get(dep.name.as_str()).copied();
+            let satisfied = found.is_some_and(|version| dep.is_satisfied_by(version));
+            if !satisfied {
+                unsatisfied.push(UnsatisfiedDep {
+                    name: dep.name.clone(),
+                    comparator: dep.comparator,
+                    required_version: dep.version.clone(),
+                    found_version: found.map(ToOwned::to_owned),
+                });
+            }
+        }
+        unsatisfied
+    }
+}
+
+/// A dependency entry that a package set fails to satisfy, as reported by
+/// [`DependencyList::satisfies_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiedDep {
+    /// The name of the required package.
+    pub name: String,
+    /// The constraint operator, when the entry carries one.
+    pub comparator: Option<Comparator>,
+    /// The version the constraint compares against, when present.
+    pub required_version: Option<String>,
+    /// The version present in the package set, or `None` when the package
+    /// is missing entirely.
+    pub found_version: Option<String>,
+}
+
+impl fmt::Display for DependencyList {
+    /// Writes the list in `.pc` field form: entries joined by `", "`, each
+    /// as `name` or `name op version`. An empty list produces an empty
+    /// string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, dep) in self.deps.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{dep}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a DependencyList {
+    type Item = &'a Dependency;
+    type IntoIter = core::slice::Iter<'a, Dependency>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for DependencyList {
+    type Item = Dependency;
+    type IntoIter = alloc::vec::IntoIter<Dependency>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deps.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn parses_names_with_and_without_constraints() {
+        let list = DependencyList::parse("openssl >= 1.1, zlib");
+        assert_eq!(list.len(), 2);
+        let deps: Vec<&Dependency> = list.iter().collect();
+        assert_eq!(deps[0].name, "openssl");
+        assert_eq!(deps[0].comparator, Some(Comparator::GreaterOrEqual));
+        assert_eq!(deps[0].version.as_deref(), Some("1.1"));
+        assert_eq!(deps[1].name, "zlib");
+        assert_eq!(deps[1].comparator, None);
+        assert_eq!(deps[1].version, None);
+    }
+
+    #[test]
+    fn get_finds_dependencies_by_name() {
+        let list = DependencyList::parse("glib-2.0 >= 2.64, gobject-2.0");
+        assert!(list.get("gobject-2.0").is_some());
+        assert!(list.get("gtk+-3.0").is_none());
+    }
+
+    #[test]
+    fn empty_field_parses_to_empty_list() {
+        assert!(DependencyList::parse("").is_empty());
+        assert!(DependencyList::parse("  ,  ").is_empty());
+    }
+
+    #[test]
+    fn single_dependency_parse_round_trips() {
+        let dep = Dependency::parse("openssl >= 1.1").unwrap();
+        assert_eq!(dep.to_string(), "openssl >= 1.1");
+        assert_eq!(Dependency::parse("zlib").unwrap().to_string(), "zlib");
+        assert!(Dependency::parse("").is_err());
+        assert!(Dependency::parse("openssl >=").is_err());
+        assert!(Dependency::parse("openssl, zlib").is_err());
+    }
+
+    #[test]
+    fn satisfaction_covers_every_comparator() {
+        let check = |entry: &str, candidate: &str| {
+            Dependency::parse(entry).unwrap().is_satisfied_by(candidate)
+        };
+        assert!(check("a = 1.0", "1.0") && !check("a = 1.0", "1.1"));
+        assert!(check("a != 1.0", "1.1") && !check("a != 1.0", "1.0"));
+        assert!(check("a < 2.0", "1.9") && !check("a < 2.0", "2.0"));
+        assert!(check("a <= 2.0", "2.0") && !check("a <= 2.0", "2.1"));
+        assert!(check("a > 1.0", "1.0.1") && !check("a > 1.0", "1.0"));
+        assert!(check("a >= 1.0", "1.0") && !check("a >= 1.0", "0.9"));
+        // A bare name is satisfied by anything.
+        assert!(check("a", "0.0.1"));
+    }
+
+    #[test]
+    fn from_packages_builds_bare_entries() {
+        let list = DependencyList::from_packages(&["a", "b", "c"]);
+        assert_eq!(list, DependencyList::parse("a, b, c"));
+    }
+
+    #[test]
+    fn merge_deduplicates_by_name() {
+        let merged = DependencyList::parse("a, b").merge(&DependencyList::parse("b, c"));
+        let names: Vec<&str> = merged.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn merge_keeps_the_stricter_constraint() {
+        let merged = DependencyList::parse("a >= 1.0, b <= 3.0, c")
+            .merge(&DependencyList::parse("a >= 2.0, b <= 2.5, c = 1.1"));
+        assert_eq!(merged.get("a").unwrap().to_string(), "a >= 2.0");
+        assert_eq!(merged.get("b").unwrap().to_string(), "b <= 2.5");
+        assert_eq!(merged.get("c").unwrap().to_string(), "c = 1.1");
+        // On a version tie, the exclusive bound is stricter.
+        let tie = DependencyList::parse("d >= 1.0").merge(&DependencyList::parse("d > 1.0"));
+        assert_eq!(tie.get("d").unwrap().to_string(), "d > 1.0");
+    }
+
+    #[test]
+    fn push_appends_an_entry() {
+        let mut list = DependencyList::new();
+        list.push(Dependency::parse("zlib").unwrap());
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn to_string_round_trips_through_parse() {
+        // Pseudo-property check over representative shapes.
+        for input in [
+            "",
+            "zlib",
+            "a, b, c",
+            "openssl >= 1.1.0, zlib",
+            "a = 1.0, b != 2.0, c < 3, d <= 4, e > 5, f >= 6",
+        ] {
+            let list = DependencyList::parse(input);
+            assert_eq!(DependencyList::parse(&list.to_string()), list);
+        }
+    }
+
+    #[test]
+    fn satisfies_all_reports_nothing_when_the_set_covers_the_list() {
+        let list = DependencyList::parse("openssl >= 1.1, zlib");
+        let packages = HashMap::from([("openssl", "1.1.1"), ("zlib", "1.2.13")]);
+        assert!(list.satisfies_all(&packages).is_empty());
+    }
+
+    #[test]
+    fn satisfies_all_reports_missing_packages() {
+        let list = DependencyList::parse("openssl >= 1.1, zlib");
+        let packages = HashMap::from([("openssl", "1.1.1")]);
+        assert_eq!(
+            list.satisfies_all(&packages),
+            [UnsatisfiedDep {
+                name: "zlib".to_owned(),
+                comparator: None,
+                required_version: None,
+                found_version: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn satisfies_all_reports_version_mismatches_with_both_versions() {
+        let list = DependencyList::parse("openssl >= 1.1");
+        let packages = HashMap::from([("openssl", "1.0.2")]);
+        assert_eq!(
+            list.satisfies_all(&packages),
+            [UnsatisfiedDep {
+                name: "openssl".to_owned(),
+                comparator: Some(Comparator::GreaterOrEqual),
+                required_version: Some("1.1".to_owned()),
+                found_version: Some("1.0.2".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn to_string_formats_canonically() {
+        assert_eq!(
+            DependencyList::parse("openssl >= 1.1.0,zlib").to_string(),
+            "openssl >= 1.1.0, zlib"
+        );
+        assert_eq!(DependencyList::new().to_string(), "");
+    }
+
+    #[test]
+    fn owned_iteration_yields_every_entry() {
+        let list = DependencyList::parse("a, b = 2.0");
+        let names: Vec<String> = list.into_iter().map(|dep| dep.name).collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+}
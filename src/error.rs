@@ -0,0 +1,220 @@
+//! Crate-wide error type.
+//!
+//! The individual modules keep their focused error enums, but callers that
+//! mix parsing, resolution and solving shouldn't need a conversion ladder;
+//! [`PkgconfError`] is the single type everything converts into, and
+//! [`Result`] the alias APIs built on it return.
+
+use std::fmt;
+
+use crate::parser::ParseError;
+use crate::queue::SolveError;
+
+/// Any error the crate can produce, with the common resolution failures
+/// lifted out of [`ParseError`] into dedicated variants.
+#[derive(Debug)]
+pub enum PkgconfError {
+    /// A `.pc` file could not be parsed or expanded.
+    Parse(ParseError),
+    /// A file could not be read.
+    Io(std::io::Error),
+    /// No `.pc` file for the named package exists in the search path.
+    PackageNotFound(String),
+    /// A package was found but its version fails the requested constraint.
+    VersionMismatch {
+        /// The requested package name.
+        name: String,
+        /// The version the located package declares.
+        found: String,
+        /// The requirement the version failed to satisfy.
+        required: String,
+    },
+    /// A `Requires:` chain loops back on itself; the packages in the cycle
+    /// are listed in reference order.
+    CircularDependency(Vec<String>),
+    /// Dependency traversal followed more `Requires:` edges than allowed.
+    MaxDepthExceeded {
+        /// The depth limit that was exceeded.
+        depth: i32,
+    },
+    /// A `${variable}` reference was never defined.
+    UndefinedVariable(String),
+    /// Two packages in a solution are declared incompatible.
+    ConflictDetected {
+        /// The package whose `Conflicts:` field matched.
+        name: String,
+        /// The conflicting package, as named by the matching entry.
+        conflicts_with: String,
+    },
+}
+
+impl fmt::Display for PkgconfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PkgconfError::Parse(err) => write!(f, "{err}"),
+            PkgconfError::Io(err) => write!(f, "i/o error: {err}"),
+            PkgconfError::PackageNotFound(name) => {
+                write!(f, "package '{name}' was not found in the search path")
+            }
+            PkgconfError::VersionMismatch {
+                name,
+                found,
+                required,
+            } => write!(
+                f,
+                "package '{name}' has version {found}, which does not satisfy '{required}'"
+            ),
+            PkgconfError::CircularDependency(cycle) => {
+                write!(f, "circular dependency detected: {}", cycle.join(" -> "))
+            }
+            PkgconfError::MaxDepthExceeded { depth } => {
+                write!(f, "dependency traversal exceeded the maximum depth of {depth}")
+            }
+            PkgconfError::UndefinedVariable(name) => {
+                write!(f, "variable '{name}' is not defined")
+            }
+            PkgconfError::ConflictDetected {
+                name,
+                conflicts_with,
+            } => write!(f, "package '{name}' conflicts with '{conflicts_with}'"),
+        }
+    }
+}
+
+impl std::error::Error for PkgconfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PkgconfError::Parse(err) => Some(err),
+            PkgconfError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for PkgconfError {
+    /// Lifts the resolution failures shared with [`ParseError`] into their
+    /// dedicated variants, wrapping everything else as [`PkgconfError::Parse`].
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::Io(err) => PkgconfError::Io(err),
+            ParseError::PackageNotFound { name } => PkgconfError::PackageNotFound(name),
+            ParseError::VersionMismatch {
+                name,
+                found,
+                required,
+            } => PkgconfError::VersionMismatch {
+                name,
+                found,
+                required,
+            },
+            ParseError::CircularDependency { cycle } => PkgconfError::CircularDependency(cycle),
+            ParseError::MaxDepthExceeded { depth } => PkgconfError::MaxDepthExceeded { depth },
+            ParseError::UndefinedVariable { name } => PkgconfError::UndefinedVariable(name),
+            other => PkgconfError::Parse(other),
+        }
+    }
+}
+
+impl From<std::io::Error> for PkgconfError {
+    fn from(err: std::io::Error) -> Self {
+        PkgconfError::Io(err)
+    }
+}
+
+impl From<SolveError> for PkgconfError {
+    fn from(err: SolveError) -> Self {
+        match err {
+            SolveError::MissingDependency { dependency, .. } => {
+                PkgconfError::PackageNotFound(dependency)
+            }
+            SolveError::CircularDependency { cycle } => PkgconfError::CircularDependency(cycle),
+            SolveError::ConflictDetected {
+                package,
+                conflicts_with,
+            } => PkgconfError::ConflictDetected {
+                name: package,
+                conflicts_with,
+            },
+        }
+    }
+}
+
+/// The crate-wide result alias.
+pub type Result<T> = std::result::Result<T, PkgconfError>;
+
+/// A non-fatal diagnostic noticed during resolution.
+///
+/// Warnings are accumulated on the client rather than printed, so library
+/// consumers decide how (and whether) to surface them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The named package's `.pc` file has no `Description:` field.
+    MissingDescription(String),
+    /// A dependency appears more than once in the same requirement list.
+    DuplicateDependency {
+        /// The package whose requirement list repeats itself.
+        package: String,
+        /// The repeated dependency name.
+        dependency: String,
+    },
+    /// The named package's version is absent or doesn't look like one.
+    SuspiciousVersion(String),
+    /// Anything else worth flagging.
+    OtherWarning(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::MissingDescription(name) => {
+                write!(f, "package '{name}' has no Description: field")
+            }
+            Warning::DuplicateDependency {
+                package,
+                dependency,
+            } => write!(
+                f,
+                "package '{package}' lists dependency '{dependency}' more than once"
+            ),
+            Warning::SuspiciousVersion(name) => {
+                write!(f, "package '{name}' has a missing or suspicious version")
+            }
+            Warning::OtherWarning(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_lift_into_dedicated_variants() {
+        let err: PkgconfError = ParseError::PackageNotFound {
+            name: "foo".to_owned(),
+        }
+        .into();
+        assert!(matches!(err, PkgconfError::PackageNotFound(name) if name == "foo"));
+        let err: PkgconfError = ParseError::MaxDepthExceeded { depth: 5 }.into();
+        assert!(matches!(err, PkgconfError::MaxDepthExceeded { depth: 5 }));
+    }
+
+    #[test]
+    fn io_errors_keep_their_source() {
+        use std::error::Error;
+        let err: PkgconfError =
+            std::io::Error::new(std::io::ErrorKind::NotFound, "missing").into();
+        assert!(err.source().is_some());
+        assert_eq!(err.to_string(), "i/o error: missing");
+    }
+
+    #[test]
+    fn solve_errors_convert() {
+        let err: PkgconfError = SolveError::ConflictDetected {
+            package: "app".to_owned(),
+            conflicts_with: "legacy".to_owned(),
+        }
+        .into();
+        assert_eq!(err.to_string(), "package 'app' conflicts with 'legacy'");
+    }
+}
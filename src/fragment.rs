@@ -0,0 +1,1398 @@
+//! Compiler and linker flag fragments.
+//!
+//! The `Cflags:` and `Libs:` fields of a `.pc` file are shell-like argument
+//! lists. [`FragmentList`] tokenises such a field, deduplicates repeated
+//! flags the way pkg-config does, and renders the result back to a string.
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// An error produced while tokenising a flag field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FragmentError {
+    /// A quoted span was never closed before the end of the input.
+    UnclosedQuote {
+        /// The quote character that was left open.
+        quote: char,
+    },
+}
+
+impl fmt::Display for FragmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FragmentError::UnclosedQuote { quote } => {
+                write!(f, "unclosed {quote} quote in flag field")
+            }
+        }
+    }
+}
+
+impl core::error::Error for FragmentError {}
+
+/// Splits a `.pc` flag field into tokens using POSIX shell quoting.
+///
+/// Tokens are separated by whitespace; single-quoted spans are taken
+/// literally; double-quoted spans honour the `\"` and `\\` escape
+/// sequences; outside quotes a backslash escapes the next character.
+/// Quotes group characters but are not included in the token. An unclosed
+/// quote is an error.
+pub fn argv_split_posix(s: &str) -> Result<Vec<String>, FragmentError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_token {
+                    args.push(core::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            '\'' => {
+                in_token = true;
+                let mut closed = false;
+                for inner in chars.by_ref() {
+                    if inner == '\'' {
+                        closed = true;
+                        break;
+                    }
+                    current.push(inner);
+                }
+                if !closed {
+                    return Err(FragmentError::UnclosedQuote { quote: '\'' });
+                }
+            }
+            '"' => {
+                in_token = true;
+                let mut closed = false;
+                while let Some(inner) = chars.next() {
+                    match inner {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' if matches!(chars.peek(), Some('"' | '\\')) => {
+                            current.push(chars.next().unwrap());
+                        }
+                        _ => current.push(inner),
+                    }
+                }
+                if !closed {
+                    return Err(FragmentError::UnclosedQuote { quote: '"' });
+                }
+            }
+            '\\' => {
+                in_token = true;
+                if let Some(&escaped) = chars.peek() {
+                    current.push(escaped);
+                    chars.next();
+                }
+            }
+            _ => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_token {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Splits a `.pc` flag field into tokens using `cmd.exe`-style quoting.
+///
+/// Only double quotes group characters, a doubled `""` inside a quoted
+/// span stands for a literal quote, and backslashes are ordinary path
+/// characters. Tokens are separated by whitespace and an unclosed quote is
+/// an error.
+pub fn argv_split_windows(s: &str) -> Result<Vec<String>, FragmentError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_token {
+                    args.push(core::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            '"' => {
+                in_token = true;
+                let mut closed = false;
+                while let Some(inner) = chars.next() {
+                    if inner != '"' {
+                        current.push(inner);
+                    } else if chars.peek() == Some(&'"') {
+                        current.push('"');
+                        chars.next();
+                    } else {
+                        closed = true;
+                        break;
+                    }
+                }
+                if !closed {
+                    return Err(FragmentError::UnclosedQuote { quote: '"' });
+                }
+            }
+            _ => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_token {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Splits a `.pc` flag field using the quoting rules of the compiling
+/// platform: [`argv_split_windows`] on Windows, [`argv_split_posix`]
+/// everywhere else.
+pub fn argv_split_auto(s: &str) -> Result<Vec<String>, FragmentError> {
+    #[cfg(windows)]
+    {
+        argv_split_windows(s)
+    }
+    #[cfg(not(windows))]
+    {
+        argv_split_posix(s)
+    }
+}
+
+/// The role a fragment plays on a compiler or linker command line.
+///
+/// Determined purely by the flag prefix; fragments that match none of the
+/// known prefixes are [`FragmentType::Other`] with the raw text attached.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FragmentType {
+    /// `-I<dir>` — a header search path.
+    IncludePath,
+    /// `-L<dir>` — a library search path.
+    LibraryPath,
+    /// `-l<name>` — a library to link.
+    Library,
+    /// `-D<macro>` — a preprocessor define.
+    Define,
+    /// `-framework <name>` — a macOS framework.
+    Framework,
+    /// `-F<dir>` — a macOS framework search path.
+    FrameworkSearchPath,
+    /// `-Wl,-rpath,<dir>` or `-rpath <dir>` — a runtime library search path.
+    Rpath,
+    /// Anything else, carried verbatim.
+    Other(String),
+}
+
+impl FragmentType {
+    /// Classifies a fragment by its flag prefix.
+    pub fn of(fragment: &str) -> FragmentType {
+        if fragment.starts_with("-I") {
+            FragmentType::IncludePath
+        } else if fragment.starts_with("-L") {
+            FragmentType::LibraryPath
+        } else if fragment.starts_with("-l") {
+            FragmentType::Library
+        } else if fragment.starts_with("-D") {
+            FragmentType::Define
+        } else if fragment == "-framework" {
+            FragmentType::Framework
+        } else if fragment.starts_with("-F") {
+            FragmentType::FrameworkSearchPath
+        } else if fragment.starts_with("-Wl,-rpath,") || fragment == "-rpath" {
+            FragmentType::Rpath
+        } else {
+            FragmentType::Other(fragment.to_owned())
+        }
+    }
+}
+
+/// A single compiler/linker flag, split into its flag letter and payload.
+///
+/// A token like `-I/usr/include` has `prefix: Some('I')` and
+/// `value: "/usr/include"`; a bare token like `/usr/lib/libm.a` has
+/// `prefix: None` and the whole token as its value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fragment {
+    /// The single-character flag letter, when the token starts with `-`.
+    pub prefix: Option<char>,
+    /// The remainder of the token after the flag letter.
+    pub value: String,
+}
+
+impl Fragment {
+    /// Splits a raw token into its prefix letter and value.
+    pub fn from_token(token: &str) -> Fragment {
+        let mut chars = token.chars();
+        if let Some('-') = chars.next()
+            && let Some(prefix) = chars.next()
+        {
+            Fragment {
+                prefix: Some(prefix),
+                value: chars.collect(),
+            }
+        } else {
+            Fragment {
+                prefix: None,
+                value: token.to_owned(),
+            }
+        }
+    }
+
+    /// Reassembles the original token: `-Ivalue` with a prefix, the bare
+    /// value without one.
+    pub fn to_flag_string(&self) -> String {
+        match self.prefix {
+            Some(prefix) => format!("-{prefix}{}", self.value),
+            None => self.value.clone(),
+        }
+    }
+
+    /// The [`FragmentType`] this fragment's flag prefix denotes.
+    pub fn kind(&self) -> FragmentType {
+        FragmentType::of(&self.to_flag_string())
+    }
+}
+
+/// Which command line a fragment list is destined for; selects the
+/// category ordering [`FragmentList::sort_canonical`] applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagClass {
+    /// Compiler flags: `-D` defines, then `-I` paths, then the rest.
+    Compile,
+    /// Linker flags: `-L` paths, then `-l` libraries, then the rest.
+    Link,
+}
+
+/// Controls how [`FragmentList::render_with_options`] emits fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Emit MSVC toolchain syntax (`/I`, `/LIBPATH:`, `name.lib`) instead
+    /// of the GCC-style flags stored in `.pc` files.
+    pub msvc_syntax: bool,
+    /// The separator placed between fragments.
+    pub separator: char,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            msvc_syntax: false,
+            separator: ' ',
+        }
+    }
+}
+
+/// Compares macOS framework names the way the host filesystem would:
+/// case-insensitively on macOS, case-sensitively everywhere else.
+fn framework_names_equal(a: &str, b: &str) -> bool {
+    if cfg!(target_os = "macos") {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Whether `path` is absolute in either Unix (`/…`) or Windows
+/// (`C:\\…`, `C:/…`) notation.
+#[cfg(feature = "std")]
+fn is_absolute_path(path: &str) -> bool {
+    if path.starts_with('/') || path.starts_with('\\') {
+        return true;
+    }
+    let mut chars = path.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(drive), Some(':'), Some('/' | '\\')) if drive.is_ascii_alphabetic()
+    )
+}
+
+/// Rewrites a single GCC-style fragment into MSVC syntax.
+///
+/// Fragments with no MSVC equivalent are returned unchanged.
+fn to_msvc(fragment: &str) -> String {
+    if let Some(dir) = fragment.strip_prefix("-I") {
+        format!("/I{dir}")
+    } else if let Some(dir) = fragment.strip_prefix("-L") {
+        format!("/LIBPATH:{dir}")
+    } else if let Some(name) = fragment.strip_prefix("-l") {
+        format!("{name}.lib")
+    } else {
+        fragment.to_owned()
+    }
+}
+
+/// An ordered list of compiler/linker flag fragments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FragmentList {
+    fragments: Vec<Fragment>,
+}
+
+impl FragmentList {
+    /// Creates an empty list.
+    pub fn new() -> FragmentList {
+        FragmentList::default()
+    }
+
+    /// Tokenises a `Cflags:`/`Libs:` field into a fragment list.
+    ///
+    /// `-framework <name>` pairs are deduplicated on the framework name
+    /// (case-insensitively on macOS, matching the filesystem).
+    pub fn parse(field: &str) -> Result<FragmentList, FragmentError> {
+        let mut list = FragmentList::new();
+        let mut tokens = argv_split_auto(field)?.into_iter();
+        while let Some(token) = tokens.next() {
+            if token == "-framework" {
+                if let Some(name) = tokens.next() {
+                    list.push_framework(name);
+                }
+            } else if token == "-rpath" {
+                if let Some(path) = tokens.next() {
+                    list.push_rpath(path);
+                }
+            } else {
+                list.push(token);
+            }
+        }
+        Ok(list)
+    }
+
+    /// Parses the stdout of a `pkg-config --cflags`/`--libs` invocation
+    /// back into a fragment list.
+    ///
+    /// The same tokenizer as [`FragmentList::parse`], but shaped for tool
+    /// output: surrounding whitespace and the trailing newline are
+    /// ignored, an empty string yields an empty list, and a stray
+    /// unclosed quote degrades to plain whitespace splitting instead of
+    /// failing — existing tools' output should never be a hard error.
+    pub fn from_pkg_config_output(s: &str) -> FragmentList {
+        let s = s.trim();
+        if s.is_empty() {
+            return FragmentList::new();
+        }
+        FragmentList::parse(s).unwrap_or_else(|_| {
+            let mut list = FragmentList::new();
+            for token in s.split_whitespace() {
+                list.push(token.to_owned());
+            }
+            list
+        })
+    }
+
+    /// Parses a `Cflags:`-style field.
+    ///
+    /// Currently identical to [`FragmentList::parse`]; kept separate so
+    /// compile-flag-specific validation has somewhere to live.
+    pub fn parse_cflags(s: &str) -> Result<FragmentList, FragmentError> {
+        FragmentList::parse(s)
+    }
+
+    /// Parses a `Libs:`-style field.
+    ///
+    /// Currently identical to [`FragmentList::parse`]; kept separate so
+    /// link-flag-specific validation has somewhere to live.
+    pub fn parse_libs(s: &str) -> Result<FragmentList, FragmentError> {
+        FragmentList::parse(s)
+    }
+
+    /// Appends a `-framework <name>` pair unless a framework of the same
+    /// name is already present.
+    fn push_framework(&mut self, name: String) {
+        if self.contains_framework(&name) {
+            return;
+        }
+        self.fragments.push(Fragment::from_token("-framework"));
+        self.fragments.push(Fragment::from_token(&name));
+    }
+
+    /// Appends a two-token `-rpath <path>` pair unless the same path is
+    /// already present.
+    fn push_rpath(&mut self, path: String) {
+        let already = self.fragments.windows(2).any(|pair| {
+            pair[0].to_flag_string() == "-rpath" && pair[1].value == path
+        });
+        if already {
+            return;
+        }
+        self.fragments.push(Fragment::from_token("-rpath"));
+        self.fragments.push(Fragment::from_token(&path));
+    }
+
+    /// Whether the list already links the named framework.
+    fn contains_framework(&self, name: &str) -> bool {
+        self.fragments.windows(2).any(|pair| {
+            pair[0].kind() == FragmentType::Framework
+                && framework_names_equal(&pair[1].value, name)
+        })
+    }
+
+    /// Appends a raw token, dropping it if an identical fragment is already
+    /// present (pkg-config's first-occurrence deduplication).
+    ///
+    /// `-l` flags are exempt and may repeat: link order is significant for
+    /// them, and [`FragmentList::dedup_preserve_link_order`] resolves the
+    /// duplicates in favour of the last occurrence.
+    pub fn push(&mut self, fragment: String) {
+        let fragment = Fragment::from_token(&fragment);
+        if fragment.prefix == Some('l') || !self.fragments.contains(&fragment) {
+            self.fragments.push(fragment);
+        }
+    }
+
+    /// Returns a copy with exact-duplicate fragments removed, keeping the
+    /// first occurrence of each.
+    pub fn dedup_first(&self) -> FragmentList {
+        let mut deduped = FragmentList::new();
+        for fragment in &self.fragments {
+            if !deduped.fragments.contains(fragment) {
+                deduped.fragments.push(fragment.clone());
+            }
+        }
+        deduped
+    }
+
+    /// Returns a copy with duplicates removed while preserving correct link
+    /// order: `-l` flags keep their *last* occurrence (linkers process
+    /// archives left to right, so the final position satisfies every
+    /// forward reference), everything else keeps its first.
+    pub fn dedup_preserve_link_order(&self) -> FragmentList {
+        let mut deduped = FragmentList::new();
+        for (i, fragment) in self.fragments.iter().enumerate() {
+            let keep = if fragment.prefix == Some('l') {
+                // Keep only the final occurrence of each library.
+                !self.fragments[i + 1..].contains(fragment)
+            } else {
+                !deduped.fragments.contains(fragment)
+            };
+            if keep {
+                deduped.fragments.push(fragment.clone());
+            }
+        }
+        deduped
+    }
+
+    /// Returns a new list containing only the fragments of the given kind.
+    ///
+    /// For [`FragmentType::Other`] the attached text must match exactly.
+    pub fn filter_by_kind(&self, kind: FragmentType) -> FragmentList {
+        let mut filtered = FragmentList::new();
+        for fragment in &self.fragments {
+            if fragment.kind() == kind {
+                filtered.fragments.push(fragment.clone());
+            }
+        }
+        filtered
+    }
+
+    /// Returns a new list with `-I` flags pointing into `system_includedirs`
+    /// and `-L` flags pointing into `system_libdirs` removed, the way
+    /// `pkg-config --cflags` omits `/usr/include`. The crate-level
+    /// [`DEFAULT_SYSTEM_INCLUDEDIRS`](crate::DEFAULT_SYSTEM_INCLUDEDIRS) and
+    /// [`DEFAULT_SYSTEM_LIBDIRS`](crate::DEFAULT_SYSTEM_LIBDIRS) constants
+    /// hold the usual arguments.
+    ///
+    /// `-F` framework search paths are filtered against
+    /// `system_framework_dirs` the same way, and rpath fragments
+    /// (`-Wl,-rpath,<dir>` or `-rpath <dir>`) against `system_rpaths`.
+    ///
+    /// Setting `PKG_CONFIG_ALLOW_SYSTEM_CFLAGS` in the environment disables
+    /// the `-I` and `-F` filtering; `PKG_CONFIG_ALLOW_SYSTEM_LIBS` disables
+    /// the `-L` filtering.
+    #[cfg(feature = "std")]
+    pub fn filter_system_paths(
+        &self,
+        system_includedirs: &[&str],
+        system_libdirs: &[&str],
+        system_framework_dirs: &[&str],
+        system_rpaths: &[&str],
+    ) -> FragmentList {
+        let allow_cflags = std::env::var_os("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS").is_some();
+        let allow_libs = std::env::var_os("PKG_CONFIG_ALLOW_SYSTEM_LIBS").is_some();
+        self.filter_system_paths_with(
+            allow_cflags,
+            allow_libs,
+            system_includedirs,
+            system_libdirs,
+            system_framework_dirs,
+            system_rpaths,
+        )
+    }
+
+    /// The environment-free core of [`FragmentList::filter_system_paths`]:
+    /// the allow flags come in as parameters, so a caller that has already
+    /// captured its configuration — like [`Client`](crate::client::Client)
+    /// — is not overridden by the ambient environment at call time.
+    #[cfg(feature = "std")]
+    pub(crate) fn filter_system_paths_with(
+        &self,
+        allow_cflags: bool,
+        allow_libs: bool,
+        system_includedirs: &[&str],
+        system_libdirs: &[&str],
+        system_framework_dirs: &[&str],
+        system_rpaths: &[&str],
+    ) -> FragmentList {
+        let mut filtered = FragmentList::new();
+        let mut fragments = self.fragments.iter();
+        while let Some(fragment) = fragments.next() {
+            let token = fragment.to_flag_string();
+            if token == "-rpath" {
+                // Two-token form: the path travels in the next fragment.
+                if let Some(path) = fragments.next()
+                    && (allow_libs || !system_rpaths.contains(&path.value.as_str()))
+                {
+                    filtered.fragments.push(fragment.clone());
+                    filtered.fragments.push(path.clone());
+                }
+                continue;
+            }
+            let system = if let Some(dir) = token.strip_prefix("-Wl,-rpath,") {
+                !allow_libs && system_rpaths.contains(&dir)
+            } else {
+                match fragment.prefix {
+                    Some('I') => {
+                        !allow_cflags && system_includedirs.contains(&fragment.value.as_str())
+                    }
+                    Some('L') => !allow_libs && system_libdirs.contains(&fragment.value.as_str()),
+                    Some('F') => {
+                        !allow_cflags && system_framework_dirs.contains(&fragment.value.as_str())
+                    }
+                    _ => false,
+                }
+            };
+            if !system {
+                filtered.fragments.push(fragment.clone());
+            }
+        }
+        filtered
+    }
+
+    /// Partitions the list into `(cflags, ldflags)`.
+    ///
+    /// Compile-side fragments are `-I`, `-D`, `-U` and the (possibly
+    /// two-token) `-isystem`, `-idirafter` and `-include` flags; everything
+    /// else — `-L`, `-l`, `-Wl,*`, frameworks, and any flag this crate does
+    /// not recognise — conservatively goes to the linker side.
+    pub fn split(&self) -> (FragmentList, FragmentList) {
+        let mut cflags = FragmentList::new();
+        let mut ldflags = FragmentList::new();
+        let mut fragments = self.fragments.iter();
+        while let Some(fragment) = fragments.next() {
+            let token = fragment.to_flag_string();
+            if matches!(token.as_str(), "-isystem" | "-idirafter" | "-include") {
+                cflags.fragments.push(fragment.clone());
+                if let Some(arg) = fragments.next() {
+                    cflags.fragments.push(arg.clone());
+                }
+            } else if matches!(fragment.prefix, Some('I' | 'D' | 'U'))
+                || token.starts_with("-isystem")
+                || token.starts_with("-idirafter")
+                || token.starts_with("-include")
+            {
+                cflags.fragments.push(fragment.clone());
+            } else if token == "-framework" || token == "-rpath" {
+                ldflags.fragments.push(fragment.clone());
+                if let Some(arg) = fragments.next() {
+                    ldflags.fragments.push(arg.clone());
+                }
+            } else {
+                ldflags.fragments.push(fragment.clone());
+            }
+        }
+        (cflags, ldflags)
+    }
+
+    /// Combines two lists, consuming both and deduplicating across the
+    /// boundary: most flags keep their first occurrence, but `-l` flags
+    /// keep the last one, matching pkgconf's library-ordering rules.
+    pub fn merge(mut self, other: FragmentList) -> FragmentList {
+        self.extend(&other);
+        self
+    }
+
+    /// Appends `other` in place, applying the same deduplication rules as
+    /// [`FragmentList::merge`].
+    pub fn extend(&mut self, other: &FragmentList) {
+        let mut fragments = other.fragments.iter();
+        while let Some(fragment) = fragments.next() {
+            if fragment.kind() == FragmentType::Framework {
+                if let Some(name) = fragments.next() {
+                    self.push_framework(name.value.clone());
+                }
+            } else if fragment.prefix == Some('l') {
+                // Libraries must link after whatever needs them, so a
+                // repeated -l flag moves to its last position.
+                self.fragments.retain(|existing| existing != fragment);
+                self.fragments.push(fragment.clone());
+            } else if !self.fragments.contains(fragment) {
+                self.fragments.push(fragment.clone());
+            }
+        }
+    }
+
+    /// Returns a copy with `sysroot` prepended to the absolute paths carried
+    /// by path-prefix flags (`-I`, `-L`, `-isystem`, `-idirafter`),
+    /// implementing `PKG_CONFIG_SYSROOT_DIR` semantics.
+    ///
+    /// Relative paths are untouched, and paths that already start with the
+    /// sysroot are left alone, so the transformation is idempotent.
+    #[cfg(feature = "std")]
+    pub fn apply_sysroot(&self, sysroot: &Path) -> FragmentList {
+        let sysroot = sysroot.display().to_string();
+        self.map_paths(|path| {
+            if is_absolute_path(path) && !path.starts_with(&sysroot) {
+                Some(format!("{sysroot}{path}"))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The inverse of [`FragmentList::apply_sysroot`]: returns a copy with
+    /// the `sysroot` prefix removed from path-bearing fragments that carry
+    /// it. Paths outside the sysroot are untouched.
+    #[cfg(feature = "std")]
+    pub fn strip_sysroot(&self, sysroot: &Path) -> FragmentList {
+        let sysroot = sysroot.display().to_string();
+        self.map_paths(|path| {
+            let stripped = path.strip_prefix(&sysroot)?;
+            if is_absolute_path(stripped) {
+                Some(stripped.to_owned())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Applies `rewrite` to the path portion of every path-bearing fragment,
+    /// including the second token of two-token forms like `-isystem /x`.
+    /// A `None` return leaves the fragment unchanged.
+    #[cfg(feature = "std")]
+    fn map_paths(&self, rewrite: impl Fn(&str) -> Option<String>) -> FragmentList {
+        let mut out = self.clone();
+        let mut rewrite_next = false;
+        for fragment in &mut out.fragments {
+            let token = fragment.to_flag_string();
+            if rewrite_next {
+                rewrite_next = false;
+                if let Some(rewritten) = rewrite(&token) {
+                    *fragment = Fragment::from_token(&rewritten);
+                }
+                continue;
+            }
+            if token == "-isystem" || token == "-idirafter" {
+                rewrite_next = true;
+                continue;
+            }
+            for flag in ["-I", "-L", "-isystem", "-idirafter"] {
+                if let Some(path) = token.strip_prefix(flag)
+                    && let Some(rewritten) = rewrite(path)
+                {
+                    *fragment = Fragment::from_token(&format!("{flag}{rewritten}"));
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the fragments joined by `separator`.
+    pub fn render(&self, separator: char) -> String {
+        self.render_with_options(&RenderOptions {
+            separator,
+            ..RenderOptions::default()
+        })
+    }
+
+    /// Renders the fragments in MSVC toolchain syntax, as pkg-config does
+    /// under `PKG_CONFIG_MSVC_SYNTAX`: `-I` becomes `/I`, `-L` becomes
+    /// `/LIBPATH:` and `-lname` becomes `name.lib`. Other fragments are
+    /// emitted verbatim.
+    pub fn render_msvc(&self) -> String {
+        self.render_with_options(&RenderOptions {
+            msvc_syntax: true,
+            ..RenderOptions::default()
+        })
+    }
+
+    /// Renders the fragments according to `options`.
+    pub fn render_with_options(&self, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        let mut fragments = self.fragments.iter();
+        while let Some(fragment) = fragments.next() {
+            let token = fragment.to_flag_string();
+            if options.msvc_syntax && fragment.kind() == FragmentType::Rpath {
+                // MSVC has no rpath equivalent; drop the path token of the
+                // two-token form along with the flag.
+                if token == "-rpath" {
+                    fragments.next();
+                }
+                continue;
+            }
+            if !out.is_empty() {
+                out.push(options.separator);
+            }
+            if options.msvc_syntax {
+                out.push_str(&to_msvc(&token));
+            } else {
+                out.push_str(&token);
+            }
+        }
+        out
+    }
+
+    /// Reorders the fragments into pkgconf's canonical emission order for
+    /// `class`, keeping the relative order within each category.
+    ///
+    /// For compile flags that is defines, include paths, then everything
+    /// else; for link flags, search paths, libraries, then everything
+    /// else. Two-token forms (`-framework name`, `-rpath dir`) move as a
+    /// unit. Ordering only — nothing is deduplicated.
+    pub fn sort_canonical(&self, class: FlagClass) -> FragmentList {
+        let mut units: Vec<Vec<Fragment>> = Vec::new();
+        let mut fragments = self.fragments.iter().cloned();
+        while let Some(fragment) = fragments.next() {
+            let token = fragment.to_flag_string();
+            let mut unit = vec![fragment];
+            if token == "-framework" || token == "-rpath" {
+                unit.extend(fragments.next());
+            }
+            units.push(unit);
+        }
+        // sort_by_key is stable, so same-category fragments keep their
+        // relative order and the result is deterministic.
+        units.sort_by_key(|unit| match (class, unit[0].kind()) {
+            (FlagClass::Compile, FragmentType::Define) => 0,
+            (FlagClass::Compile, FragmentType::IncludePath) => 1,
+            (FlagClass::Link, FragmentType::LibraryPath) => 0,
+            (FlagClass::Link, FragmentType::Library) => 1,
+            _ => 2,
+        });
+        FragmentList {
+            fragments: units.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Renders each fragment followed by a NUL byte, in list order.
+    ///
+    /// Spaces inside a fragment (e.g. a path) then survive shell
+    /// consumption via `xargs -0` or `read -d $'\0'`, which
+    /// space-separated output cannot guarantee.
+    pub fn render_nul_separated(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for fragment in &self.fragments {
+            out.extend_from_slice(fragment.to_flag_string().as_bytes());
+            out.push(0);
+        }
+        out
+    }
+
+    /// Renders each fragment followed by a newline, in list order.
+    pub fn render_newline_separated(&self) -> String {
+        let mut out = String::new();
+        for fragment in &self.fragments {
+            out.push_str(&fragment.to_flag_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Returns each fragment as its own flag string, for interop with
+    /// tooling that works on `Vec<String>` flag lists.
+    pub fn to_vec(&self) -> Vec<String> {
+        self.fragments.iter().map(Fragment::to_flag_string).collect()
+    }
+
+    /// Iterates over the fragments in order.
+    pub fn iter(&self) -> core::slice::Iter<'_, Fragment> {
+        self.fragments.iter()
+    }
+
+    /// The number of fragments in the list.
+    pub fn len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Whether the list contains no fragments.
+    pub fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a FragmentList {
+    type Item = &'a Fragment;
+    type IntoIter = core::slice::Iter<'a, Fragment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<String> for FragmentList {
+    /// Collects pre-split flags, one fragment per element, applying the
+    /// usual [`FragmentList::push`] deduplication.
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> FragmentList {
+        let mut list = FragmentList::new();
+        for flag in iter {
+            list.push(flag);
+        }
+        list
+    }
+}
+
+impl<'a> FromIterator<&'a str> for FragmentList {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> FragmentList {
+        iter.into_iter().map(str::to_owned).collect()
+    }
+}
+
+impl IntoIterator for FragmentList {
+    type Item = Fragment;
+    type IntoIter = alloc::vec::IntoIter<Fragment>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fragments.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkg_config_output_round_trips() {
+        // What the binary shim prints for --cflags / --libs, trailing
+        // newline included.
+        for output in [
+            "-I/usr/include/glib-2.0 -DG_LOG_DOMAIN=app\n",
+            "-L/opt/lib -lfoo -lbar -Wl,-rpath,/opt/lib\n",
+            "-framework Cocoa -F/Library/Frameworks\n",
+        ] {
+            let list = FragmentList::from_pkg_config_output(output);
+            assert_eq!(list.render(' '), output.trim());
+            let reparsed = FragmentList::from_pkg_config_output(&list.render(' '));
+            assert_eq!(reparsed, list);
+        }
+    }
+
+    #[test]
+    fn pkg_config_output_tolerates_empty_and_malformed_input() {
+        assert!(FragmentList::from_pkg_config_output("").is_empty());
+        assert!(FragmentList::from_pkg_config_output("  \n").is_empty());
+        // An unclosed quote degrades to whitespace splitting.
+        let list = FragmentList::from_pkg_config_output("-lfoo \"-lbar");
+        assert_eq!(list.render(' '), "-lfoo \"-lbar");
+    }
+
+    #[test]
+    fn field_specific_parsers_match_the_common_one() {
+        let field = "-I/usr/include -lfoo";
+        assert_eq!(
+            FragmentList::parse_cflags(field).unwrap(),
+            FragmentList::parse(field).unwrap()
+        );
+        assert_eq!(
+            FragmentList::parse_libs(field).unwrap(),
+            FragmentList::parse(field).unwrap()
+        );
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(
+            argv_split_posix("-I/usr/include -DFOO  -lbar").unwrap(),
+            vec!["-I/usr/include", "-DFOO", "-lbar"]
+        );
+    }
+
+    #[test]
+    fn respects_quotes() {
+        assert_eq!(
+            argv_split_posix(r#"-I"/opt/My Lib/include" -DNAME='a b'"#).unwrap(),
+            vec!["-I/opt/My Lib/include", "-DNAME=a b"]
+        );
+    }
+
+    #[test]
+    fn backslash_escapes_next_char() {
+        assert_eq!(argv_split_posix(r"-I/opt/My\ Lib").unwrap(), vec!["-I/opt/My Lib"]);
+    }
+
+    #[test]
+    fn double_quotes_honour_escape_sequences() {
+        assert_eq!(
+            argv_split_posix(r#"-DPATH="a \"b\" c\\d""#).unwrap(),
+            vec![r#"-DPATH=a "b" c\d"#]
+        );
+    }
+
+    #[test]
+    fn unclosed_quote_is_an_error() {
+        assert_eq!(
+            argv_split_posix(r#"-DFOO="bar"#),
+            Err(FragmentError::UnclosedQuote { quote: '"' })
+        );
+        assert_eq!(
+            argv_split_posix("-DFOO='bar"),
+            Err(FragmentError::UnclosedQuote { quote: '\'' })
+        );
+    }
+
+    #[test]
+    fn windows_split_treats_doubled_quotes_as_literals() {
+        assert_eq!(
+            argv_split_windows(r#"-I"C:\My Lib\include" -DNAME="a ""b"" c""#).unwrap(),
+            vec![r"-IC:\My Lib\include", r#"-DNAME=a "b" c"#]
+        );
+        // Backslashes are path separators, never escapes.
+        assert_eq!(
+            argv_split_windows(r"-LC:\MinGW\lib").unwrap(),
+            vec![r"-LC:\MinGW\lib"]
+        );
+    }
+
+    #[test]
+    fn windows_split_collapses_spaces_and_rejects_unclosed_quotes() {
+        assert_eq!(
+            argv_split_windows("-lfoo   -lbar").unwrap(),
+            vec!["-lfoo", "-lbar"]
+        );
+        assert_eq!(argv_split_windows("").unwrap(), Vec::<String>::new());
+        assert_eq!(
+            argv_split_windows(r#"-DFOO="bar"#),
+            Err(FragmentError::UnclosedQuote { quote: '"' })
+        );
+    }
+
+    #[test]
+    fn auto_split_matches_the_platform_flavour() {
+        let input = "-lfoo  -lbar";
+        #[cfg(windows)]
+        assert_eq!(argv_split_auto(input), argv_split_windows(input));
+        #[cfg(not(windows))]
+        assert_eq!(argv_split_auto(input), argv_split_posix(input));
+    }
+
+    #[test]
+    fn posix_split_collapses_spaces_and_accepts_empty_input() {
+        assert_eq!(
+            argv_split_posix("-lfoo   -lbar").unwrap(),
+            vec!["-lfoo", "-lbar"]
+        );
+        assert_eq!(argv_split_posix("").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn split_then_join_round_trips_for_plain_flags() {
+        // Pseudo-property check: for tokens without quoting metacharacters,
+        // joining and re-splitting must reproduce the token list exactly.
+        for n in 0..32 {
+            let tokens: Vec<String> = (0..n)
+                .map(|i| format!("-I/usr/include/pkg{i}-{}", (n * 31 + i) % 7))
+                .collect();
+            let joined = tokens.join(" ");
+            assert_eq!(argv_split_posix(&joined).unwrap(), tokens);
+        }
+    }
+
+    #[test]
+    fn sort_canonical_orders_compile_flags_by_category() {
+        let list =
+            FragmentList::parse("-I/opt/b/include -DFOO -pthread -I/opt/a/include -DBAR")
+                .unwrap();
+        assert_eq!(
+            list.sort_canonical(FlagClass::Compile).render(' '),
+            "-DFOO -DBAR -I/opt/b/include -I/opt/a/include -pthread"
+        );
+    }
+
+    #[test]
+    fn sort_canonical_orders_link_flags_and_keeps_pairs_together() {
+        let list = FragmentList::parse(
+            "-lfoo -framework Cocoa -L/opt/b/lib -pthread -lbar -L/opt/a/lib",
+        )
+        .unwrap();
+        assert_eq!(
+            list.sort_canonical(FlagClass::Link).render(' '),
+            "-L/opt/b/lib -L/opt/a/lib -lfoo -lbar -framework Cocoa -pthread"
+        );
+    }
+
+    #[test]
+    fn nul_separated_output_round_trips_spaced_paths() {
+        let list = FragmentList::parse(r#"-I"/opt/My Lib/include" -lfoo"#).unwrap();
+        let rendered = list.render_nul_separated();
+        assert_eq!(rendered.last(), Some(&0));
+        let parsed: Vec<String> = rendered
+            .split(|&byte| byte == 0)
+            .filter(|token| !token.is_empty())
+            .map(|token| String::from_utf8(token.to_vec()).unwrap())
+            .collect();
+        assert_eq!(parsed, list.to_vec());
+    }
+
+    #[test]
+    fn newline_separated_output_keeps_fragment_order() {
+        let list = FragmentList::parse("-I/usr/local/include -DFOO -lfoo").unwrap();
+        assert_eq!(
+            list.render_newline_separated(),
+            "-I/usr/local/include\n-DFOO\n-lfoo\n"
+        );
+    }
+
+    #[test]
+    fn parse_deduplicates_repeated_flags() {
+        let list = FragmentList::parse("-I/usr/include -lfoo -I/usr/include").unwrap();
+        assert_eq!(list.render(' '), "-I/usr/include -lfoo");
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn empty_field_parses_to_empty_list() {
+        assert!(FragmentList::parse("  ").unwrap().is_empty());
+    }
+
+    // The environment is process-global and the client tests exercise the
+    // same `PKG_CONFIG_ALLOW_SYSTEM_*` filtering, so every test that sets
+    // or reads these variables takes the crate-wide lock.
+    #[cfg(feature = "std")]
+    use crate::ENV_LOCK;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn filter_system_paths_drops_default_system_dirs() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let list = FragmentList::parse("-I/usr/include -I/opt/include -L/usr/lib -L/opt/lib -lfoo")
+            .unwrap();
+        let filtered = list.filter_system_paths(
+            crate::DEFAULT_SYSTEM_INCLUDEDIRS,
+            crate::DEFAULT_SYSTEM_LIBDIRS,
+            &[],
+            &[],
+        );
+        assert_eq!(filtered.render(' '), "-I/opt/include -L/opt/lib -lfoo");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn filter_system_paths_honours_custom_dir_lists() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let list = FragmentList::parse("-I/sdk/include -L/sdk/lib -lfoo").unwrap();
+        let filtered = list.filter_system_paths(&["/sdk/include"], &["/sdk/lib"], &[], &[]);
+        assert_eq!(filtered.render(' '), "-lfoo");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn allow_system_env_vars_disable_filtering() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let list = FragmentList::parse("-I/usr/include -L/usr/lib").unwrap();
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe { std::env::set_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS", "1") };
+        let filtered = list.filter_system_paths(
+            crate::DEFAULT_SYSTEM_INCLUDEDIRS,
+            crate::DEFAULT_SYSTEM_LIBDIRS,
+            &[],
+            &[],
+        );
+        assert_eq!(filtered.render(' '), "-I/usr/include");
+        unsafe { std::env::set_var("PKG_CONFIG_ALLOW_SYSTEM_LIBS", "1") };
+        let filtered = list.filter_system_paths(
+            crate::DEFAULT_SYSTEM_INCLUDEDIRS,
+            crate::DEFAULT_SYSTEM_LIBDIRS,
+            &[],
+            &[],
+        );
+        assert_eq!(filtered.render(' '), "-I/usr/include -L/usr/lib");
+        unsafe {
+            std::env::remove_var("PKG_CONFIG_ALLOW_SYSTEM_CFLAGS");
+            std::env::remove_var("PKG_CONFIG_ALLOW_SYSTEM_LIBS");
+        }
+    }
+
+    #[test]
+    fn fragment_splits_prefix_and_value() {
+        assert_eq!(
+            Fragment::from_token("-I/usr/include"),
+            Fragment {
+                prefix: Some('I'),
+                value: "/usr/include".to_owned(),
+            }
+        );
+        assert_eq!(
+            Fragment::from_token("/usr/lib/libm.a"),
+            Fragment {
+                prefix: None,
+                value: "/usr/lib/libm.a".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn fragment_iteration_round_trips_through_flag_strings() {
+        let list = FragmentList::parse("-I/usr/include -DFOO=1 -lfoo /usr/lib/libm.a").unwrap();
+        let joined: Vec<String> = list.iter().map(Fragment::to_flag_string).collect();
+        assert_eq!(joined.join(" "), list.render(' '));
+        let owned: Vec<String> = list
+            .clone()
+            .into_iter()
+            .map(|fragment| fragment.to_flag_string())
+            .collect();
+        assert_eq!(owned.join(" "), list.render(' '));
+    }
+
+    #[test]
+    fn classifies_fragments_by_prefix() {
+        assert_eq!(FragmentType::of("-I/usr/include"), FragmentType::IncludePath);
+        assert_eq!(FragmentType::of("-L/usr/lib"), FragmentType::LibraryPath);
+        assert_eq!(FragmentType::of("-lfoo"), FragmentType::Library);
+        assert_eq!(FragmentType::of("-DFOO=1"), FragmentType::Define);
+        assert_eq!(FragmentType::of("-framework"), FragmentType::Framework);
+        assert_eq!(
+            FragmentType::of("-pthread"),
+            FragmentType::Other("-pthread".to_owned())
+        );
+    }
+
+    #[test]
+    fn filter_by_kind_selects_matching_fragments() {
+        let list = FragmentList::parse("-I/a -lfoo -I/b -DX -pthread").unwrap();
+        assert_eq!(
+            list.filter_by_kind(FragmentType::IncludePath).render(' '),
+            "-I/a -I/b"
+        );
+        assert_eq!(list.filter_by_kind(FragmentType::Library).render(' '), "-lfoo");
+        assert_eq!(
+            list.filter_by_kind(FragmentType::Other("-pthread".to_owned()))
+                .render(' '),
+            "-pthread"
+        );
+    }
+
+    #[test]
+    fn recognises_macos_framework_flags() {
+        let list = FragmentList::parse(
+            "-F/Library/Frameworks -framework CoreFoundation -framework CoreAudio -I/usr/include",
+        )
+        .unwrap();
+        let kinds: Vec<FragmentType> = list.iter().map(Fragment::kind).collect();
+        assert_eq!(kinds[0], FragmentType::FrameworkSearchPath);
+        assert_eq!(kinds[1], FragmentType::Framework);
+        assert_eq!(
+            list.render(' '),
+            "-F/Library/Frameworks -framework CoreFoundation -framework CoreAudio -I/usr/include"
+        );
+    }
+
+    #[test]
+    fn frameworks_deduplicate_on_name() {
+        let list = FragmentList::parse(
+            "-framework CoreFoundation -framework CoreAudio -framework CoreFoundation",
+        )
+        .unwrap();
+        assert_eq!(
+            list.render(' '),
+            "-framework CoreFoundation -framework CoreAudio"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_framework_dirs_are_filterable() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let list =
+            FragmentList::parse("-F/System/Library/Frameworks -F/opt/Frameworks -framework Foo")
+                .unwrap();
+        let filtered = list.filter_system_paths(&[], &[], &["/System/Library/Frameworks"], &[]);
+        assert_eq!(filtered.render(' '), "-F/opt/Frameworks -framework Foo");
+    }
+
+    #[test]
+    fn split_separates_compile_and_link_fragments() {
+        // Representative of what a gtk+-3.0 dependency tree produces.
+        let list = FragmentList::parse(
+            "-I/usr/include/gtk-3.0 -I/usr/include/glib-2.0 -DG_DISABLE_CAST_CHECKS \
+             -isystem /opt/include -pthread -L/usr/lib -Wl,--export-dynamic \
+             -lgtk-3 -lgdk-3 -lglib-2.0",
+        )
+        .unwrap();
+        let (cflags, ldflags) = list.split();
+        assert_eq!(
+            cflags.render(' '),
+            "-I/usr/include/gtk-3.0 -I/usr/include/glib-2.0 -DG_DISABLE_CAST_CHECKS -isystem /opt/include"
+        );
+        assert_eq!(
+            ldflags.render(' '),
+            "-pthread -L/usr/lib -Wl,--export-dynamic -lgtk-3 -lgdk-3 -lglib-2.0"
+        );
+    }
+
+    #[test]
+    fn split_sends_unknown_flags_to_ldflags() {
+        let (cflags, ldflags) = FragmentList::parse("--whatever -Irel").unwrap().split();
+        assert_eq!(cflags.render(' '), "-Irel");
+        assert_eq!(ldflags.render(' '), "--whatever");
+    }
+
+    #[test]
+    fn to_vec_and_from_iter_round_trip() {
+        let list = FragmentList::parse("-I/usr/include -DFOO=1 -lfoo /usr/lib/libm.a").unwrap();
+        assert_eq!(
+            list.to_vec(),
+            vec!["-I/usr/include", "-DFOO=1", "-lfoo", "/usr/lib/libm.a"]
+        );
+        let collected: FragmentList = list.to_vec().into_iter().collect();
+        assert_eq!(collected.render(' '), list.render(' '));
+        let from_strs: FragmentList = ["-I/a", "-DX"].into_iter().collect();
+        assert_eq!(from_strs.render(' '), "-I/a -DX");
+    }
+
+    #[test]
+    fn recognises_rpath_fragments() {
+        let list = FragmentList::parse("-Wl,-rpath,/usr/lib -rpath /opt/lib -L/usr/lib").unwrap();
+        let kinds: Vec<FragmentType> = list.iter().map(Fragment::kind).collect();
+        assert_eq!(kinds[0], FragmentType::Rpath);
+        assert_eq!(kinds[1], FragmentType::Rpath);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_rpaths_are_filtered() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let list =
+            FragmentList::parse("-Wl,-rpath,/usr/lib -rpath /usr/lib -rpath /opt/lib -lfoo")
+                .unwrap();
+        let filtered = list.filter_system_paths(&[], &[], &[], &["/usr/lib"]);
+        assert_eq!(filtered.render(' '), "-rpath /opt/lib -lfoo");
+    }
+
+    #[test]
+    fn render_msvc_omits_rpath_fragments() {
+        let list = FragmentList::parse("-L/usr/lib -Wl,-rpath,/usr/lib -rpath /opt/lib -lfoo")
+            .unwrap();
+        assert_eq!(list.render_msvc(), "/LIBPATH:/usr/lib foo.lib");
+    }
+
+    #[test]
+    fn dedup_preserve_link_order_keeps_last_library_occurrence() {
+        let list = FragmentList::parse("-lfoo -lbar -lfoo").unwrap();
+        assert_eq!(list.dedup_preserve_link_order().render(' '), "-lbar -lfoo");
+    }
+
+    #[test]
+    fn dedup_preserve_link_order_keeps_first_non_library_occurrence() {
+        let mut list = FragmentList::parse("-lfoo -lbar -lfoo").unwrap();
+        list.push("-I/a".to_owned());
+        list.push("-DX".to_owned());
+        assert_eq!(
+            list.dedup_preserve_link_order().render(' '),
+            "-lbar -lfoo -I/a -DX"
+        );
+    }
+
+    #[test]
+    fn dedup_first_always_keeps_first_occurrence() {
+        let list = FragmentList::parse("-lfoo -lbar -lfoo").unwrap();
+        assert_eq!(list.dedup_first().render(' '), "-lfoo -lbar");
+    }
+
+    #[test]
+    fn merge_keeps_first_include_and_last_library_occurrence() {
+        let a = FragmentList::parse("-I/usr/include -DFOO -lfoo -lbar").unwrap();
+        let b = FragmentList::parse("-I/usr/include -DBAR -lfoo").unwrap();
+        let merged = a.merge(b);
+        assert_eq!(merged.render(' '), "-I/usr/include -DFOO -lbar -DBAR -lfoo");
+    }
+
+    #[test]
+    fn merge_handles_empty_lists() {
+        let list = FragmentList::parse("-lfoo").unwrap();
+        assert_eq!(list.clone().merge(FragmentList::new()), list);
+        assert_eq!(FragmentList::new().merge(list.clone()), list);
+        assert!(FragmentList::new().merge(FragmentList::new()).is_empty());
+    }
+
+    #[test]
+    fn extend_preserves_unknown_flags() {
+        let mut list = FragmentList::parse("-pthread").unwrap();
+        list.extend(&FragmentList::parse("--whatever -pthread -lm").unwrap());
+        assert_eq!(list.render(' '), "-pthread --whatever -lm");
+    }
+
+    #[test]
+    fn render_msvc_rewrites_recognised_flags() {
+        let list = FragmentList::parse("-I/usr/include -L/usr/lib -lfoo -DFOO=1 --whatever").unwrap();
+        assert_eq!(
+            list.render_msvc(),
+            "/I/usr/include /LIBPATH:/usr/lib foo.lib -DFOO=1 --whatever"
+        );
+    }
+
+    #[test]
+    fn render_with_options_defaults_match_render() {
+        let list = FragmentList::parse("-I/usr/include -lfoo").unwrap();
+        assert_eq!(list.render_with_options(&RenderOptions::default()), list.render(' '));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sysroot_is_prepended_to_path_flags() {
+        let list =
+            FragmentList::parse("-I/usr/include -L/usr/lib -isystem /usr/local/include -DFOO -lfoo")
+                .unwrap();
+        assert_eq!(
+            list.apply_sysroot(Path::new("/sysroot")).render(' '),
+            "-I/sysroot/usr/include -L/sysroot/usr/lib -isystem /sysroot/usr/local/include -DFOO -lfoo"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn apply_sysroot_is_idempotent() {
+        let list = FragmentList::parse("-I/sysroot/usr/include -L/other/lib").unwrap();
+        let once = list.apply_sysroot(Path::new("/sysroot"));
+        assert_eq!(once.render(' '), "-I/sysroot/usr/include -L/sysroot/other/lib");
+        assert_eq!(once.apply_sysroot(Path::new("/sysroot")), once);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn apply_sysroot_skips_relative_paths() {
+        let list = FragmentList::parse("-Iinclude -Lbuild/lib").unwrap();
+        assert_eq!(
+            list.apply_sysroot(Path::new("/sysroot")).render(' '),
+            "-Iinclude -Lbuild/lib"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn apply_sysroot_recognises_windows_absolute_paths() {
+        let list = FragmentList::parse(r"-IC:/sdk/include '-IC:\sdk\inc' -Irelative").unwrap();
+        assert_eq!(
+            list.apply_sysroot(Path::new("/sysroot")).render(' '),
+            r"-I/sysrootC:/sdk/include -I/sysrootC:\sdk\inc -Irelative"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strip_sysroot_inverts_apply_sysroot() {
+        let list = FragmentList::parse("-I/usr/include -L/usr/lib -lfoo").unwrap();
+        let prefixed = list.apply_sysroot(Path::new("/sysroot"));
+        assert_eq!(prefixed.strip_sysroot(Path::new("/sysroot")), list);
+        // Paths outside the sysroot are untouched.
+        assert_eq!(list.strip_sysroot(Path::new("/sysroot")), list);
+    }
+}
@@ -0,0 +1,155 @@
+//! A pure-Rust implementation of the pkg-config `.pc` file format and the
+//! surrounding flag-resolution machinery, modelled after `pkgconf`'s
+//! `libpkgconf` library.
+//!
+//! The crate is organised around three core concerns:
+//!
+//! * [`parser`] — reading `.pc` files into structured [`parser::PcFile`]
+//!   values and expanding `${variable}` references.
+//! * [`fragment`] — tokenising and rendering compiler/linker flag lists
+//!   (`Cflags:`, `Libs:`).
+//! * [`version`] — RPM-style version comparison as used by pkg-config
+//!   version constraints.
+//!
+//! Cross-compilation settings live in [`personality`], and [`client`]
+//! ties everything to the search paths and environment of a host system.
+//!
+//! Building with `--no-default-features` drops the `std` feature and with
+//! it everything that touches the filesystem or environment, leaving a
+//! `no_std + alloc` crate: [`parser`], [`version`], [`dependency`] and
+//! [`fragment`] remain available for embedded and WASM consumers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// The test harness links std regardless, so unit tests may name it even
+// in a `no_std` build.
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+/// Serialises tests that read or mutate process-global `PKG_CONFIG_*`
+/// environment variables. The default test runner is multithreaded and
+/// the environment is shared across every module, so all such tests must
+/// hold this one lock — per-module locks would still race each other.
+#[cfg(all(test, feature = "std"))]
+pub(crate) static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "build-script")]
+pub mod build;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod client;
+pub mod dependency;
+#[cfg(feature = "std")]
+pub mod error;
+pub mod fragment;
+
+/// Directories searched for `.pc` files when no environment overrides are
+/// configured.
+#[cfg(unix)]
+pub const DEFAULT_PKGCONFIG_PATH: &[&str] = &[
+    "/usr/local/lib/pkgconfig",
+    "/usr/local/share/pkgconfig",
+    "/usr/lib/pkgconfig",
+    "/usr/share/pkgconfig",
+];
+
+/// Directories searched for `.pc` files when no environment overrides are
+/// configured; the common MinGW/MSYS2 installation layouts.
+#[cfg(windows)]
+pub const DEFAULT_PKGCONFIG_PATH: &[&str] = &[
+    "C:\\msys64\\mingw64\\lib\\pkgconfig",
+    "C:\\msys64\\mingw64\\share\\pkgconfig",
+    "C:\\MinGW\\lib\\pkgconfig",
+    "C:\\MinGW\\share\\pkgconfig",
+];
+
+/// Directories searched for `.pc` files when no environment overrides are
+/// configured; platforms without a convention get none.
+#[cfg(all(not(unix), not(windows)))]
+pub const DEFAULT_PKGCONFIG_PATH: &[&str] = &[];
+
+/// Header search paths the toolchain already knows about; `-I` flags
+/// pointing at these are omitted from `--cflags`-style output.
+#[cfg(unix)]
+pub const DEFAULT_SYSTEM_INCLUDEDIRS: &[&str] = &["/usr/include"];
+
+/// Header search paths the toolchain already knows about; `-I` flags
+/// pointing at these are omitted from `--cflags`-style output. MSVC
+/// reads `%INCLUDE%` instead, so only the MinGW/MSYS2 layouts appear here.
+#[cfg(windows)]
+pub const DEFAULT_SYSTEM_INCLUDEDIRS: &[&str] =
+    &["C:\\msys64\\mingw64\\include", "C:\\MinGW\\include"];
+
+/// Header search paths the toolchain already knows about; none on
+/// platforms without a convention.
+#[cfg(all(not(unix), not(windows)))]
+pub const DEFAULT_SYSTEM_INCLUDEDIRS: &[&str] = &[];
+
+/// Library search paths the toolchain already knows about; `-L` flags
+/// pointing at these are omitted from `--libs`-style output.
+#[cfg(unix)]
+pub const DEFAULT_SYSTEM_LIBDIRS: &[&str] = &["/usr/lib", "/usr/lib64", "/lib", "/lib64"];
+
+/// Library search paths the toolchain already knows about; `-L` flags
+/// pointing at these are omitted from `--libs`-style output. MSVC reads
+/// `%LIB%` instead, so only the MinGW/MSYS2 layouts appear here.
+#[cfg(windows)]
+pub const DEFAULT_SYSTEM_LIBDIRS: &[&str] = &["C:\\msys64\\mingw64\\lib", "C:\\MinGW\\lib"];
+
+/// Library search paths the toolchain already knows about; none on
+/// platforms without a convention.
+#[cfg(all(not(unix), not(windows)))]
+pub const DEFAULT_SYSTEM_LIBDIRS: &[&str] = &[];
+
+/// How many `Requires:` edges a dependency traversal may follow before
+/// it is assumed to be cyclic or degenerate.
+pub const DEFAULT_MAX_TRAVERSAL_DEPTH: i32 = 2000;
+
+/// The pkg-config version this implementation is compatible with, as
+/// reported by `pkg-config --version`.
+pub const PKGCONFIG_COMPAT_VERSION: &str = "0.29.2";
+
+/// Internal entry points re-exported for the criterion benchmarks.
+///
+/// Only available with the `bench` feature; not part of the public API.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench_internals {
+    /// Parses `.pc` content from an in-memory string.
+    pub fn parse_str(content: &str) -> Result<crate::parser::PcFile, crate::parser::ParseError> {
+        crate::parser::PcFile::parse_str(content)
+    }
+
+    /// The single-threaded search-path scan.
+    pub fn list_all_sequential(
+        client: &crate::client::Client,
+    ) -> Result<Vec<(String, String)>, crate::parser::ParseError> {
+        client.list_all_sequential()
+    }
+
+    /// The rayon-backed search-path scan.
+    #[cfg(feature = "parallel")]
+    pub fn list_all_parallel(
+        client: &crate::client::Client,
+    ) -> Result<Vec<(String, String)>, crate::parser::ParseError> {
+        client.list_all_parallel()
+    }
+}
+pub mod parser;
+#[cfg(feature = "std")]
+pub mod personality;
+#[cfg(feature = "std")]
+pub mod pkg;
+#[cfg(feature = "std")]
+pub mod queue;
+pub mod version;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+#[cfg(feature = "std")]
+pub use error::{PkgconfError, Result};
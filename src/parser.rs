@@ -0,0 +1,2095 @@
+//! Parsing of pkg-config `.pc` files.
+//!
+//! A `.pc` file consists of variable assignments (`prefix=/usr`) and keyword
+//! fields (`Cflags: -I${includedir}`). Fields may reference variables with
+//! `${name}` syntax; [`PcFile::resolve_variables`] and
+//! [`PcFile::resolve_field`] perform the expansion.
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "std")]
+use indexmap::IndexMap;
+// Without std there is no default hasher, so both maps fall back to the
+// sorted `alloc` map: variables iterate by name instead of by definition
+// order, but the expansion semantics are unchanged.
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as HashMap;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as IndexMap;
+
+// The flag-field tokenizers live next to the fragment machinery they feed,
+// but they are part of the parsing surface, so they are re-exported here.
+pub use crate::fragment::{argv_split_auto, argv_split_posix, argv_split_windows};
+
+/// The well-known field keywords of a `.pc` file.
+///
+/// Field names are matched case-insensitively, per the pkg-config
+/// specification; see [`Keyword::from_str_case_insensitive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Keyword {
+    Name,
+    Description,
+    Url,
+    Version,
+    Requires,
+    RequiresPrivate,
+    Conflicts,
+    Provides,
+    Cflags,
+    CflagsPrivate,
+    Libs,
+    LibsPrivate,
+}
+
+impl Keyword {
+    /// Matches a field name to its [`Keyword`], ignoring ASCII case.
+    ///
+    /// The pkg-config spec treats field names as case-insensitive, and some
+    /// real-world `.pc` files (notably Homebrew-generated ones) capitalise
+    /// them differently, so `"CFLAGS"`, `"Cflags"` and `"cflags"` all map to
+    /// [`Keyword::Cflags`].
+    pub fn from_str_case_insensitive(s: &str) -> Option<Keyword> {
+        // `.pc` field names are short ASCII identifiers, so an allocation-free
+        // eq_ignore_ascii_case chain is all we need here.
+        let matches = |kw: &str| s.eq_ignore_ascii_case(kw);
+        if matches("name") {
+            Some(Keyword::Name)
+        } else if matches("description") {
+            Some(Keyword::Description)
+        } else if matches("url") {
+            Some(Keyword::Url)
+        } else if matches("version") {
+            Some(Keyword::Version)
+        } else if matches("requires") {
+            Some(Keyword::Requires)
+        } else if matches("requires.private") {
+            Some(Keyword::RequiresPrivate)
+        } else if matches("conflicts") {
+            Some(Keyword::Conflicts)
+        } else if matches("provides") {
+            Some(Keyword::Provides)
+        } else if matches("cflags") {
+            Some(Keyword::Cflags)
+        } else if matches("cflags.private") {
+            Some(Keyword::CflagsPrivate)
+        } else if matches("libs") {
+            Some(Keyword::Libs)
+        } else if matches("libs.private") {
+            Some(Keyword::LibsPrivate)
+        } else {
+            None
+        }
+    }
+
+    /// The canonical emission order of fields in a `.pc` file.
+    pub const CANONICAL_ORDER: &[Keyword] = &[
+        Keyword::Name,
+        Keyword::Description,
+        Keyword::Version,
+        Keyword::Url,
+        Keyword::Requires,
+        Keyword::RequiresPrivate,
+        Keyword::Conflicts,
+        Keyword::Provides,
+        Keyword::Cflags,
+        Keyword::CflagsPrivate,
+        Keyword::Libs,
+        Keyword::LibsPrivate,
+    ];
+
+    /// The canonical (as-documented) spelling of the field name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Keyword::Name => "Name",
+            Keyword::Description => "Description",
+            Keyword::Url => "URL",
+            Keyword::Version => "Version",
+            Keyword::Requires => "Requires",
+            Keyword::RequiresPrivate => "Requires.private",
+            Keyword::Conflicts => "Conflicts",
+            Keyword::Provides => "Provides",
+            Keyword::Cflags => "Cflags",
+            Keyword::CflagsPrivate => "Cflags.private",
+            Keyword::Libs => "Libs",
+            Keyword::LibsPrivate => "Libs.private",
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keyword {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Keywords serialize as their lowercase `.pc` spelling so that
+        // serialized maps read like the file format itself.
+        let name = match self {
+            Keyword::Name => "name",
+            Keyword::Description => "description",
+            Keyword::Url => "url",
+            Keyword::Version => "version",
+            Keyword::Requires => "requires",
+            Keyword::RequiresPrivate => "requires.private",
+            Keyword::Conflicts => "conflicts",
+            Keyword::Provides => "provides",
+            Keyword::Cflags => "cflags",
+            Keyword::CflagsPrivate => "cflags.private",
+            Keyword::Libs => "libs",
+            Keyword::LibsPrivate => "libs.private",
+        };
+        serializer.serialize_str(name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keyword {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Keyword, D::Error> {
+        struct KeywordVisitor;
+
+        impl serde::de::Visitor<'_> for KeywordVisitor {
+            type Value = Keyword;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a pkg-config field name")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Keyword, E> {
+                Keyword::from_str_case_insensitive(value)
+                    .ok_or_else(|| E::custom(format!("unknown pkg-config field name: {value:?}")))
+            }
+        }
+
+        deserializer.deserialize_str(KeywordVisitor)
+    }
+}
+
+/// An error produced while reading or parsing a `.pc` file.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The file could not be read.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// Variable expansion exceeded the configured recursion depth.
+    VariableExpansionDepthExceeded {
+        /// The variable whose expansion blew the limit.
+        variable: String,
+        /// The depth limit that was exceeded.
+        depth: u32,
+    },
+    /// No `.pc` file for the requested package exists in the search path.
+    PackageNotFound {
+        /// The requested package name.
+        name: String,
+    },
+    /// A package's `Requires:` chain loops back on itself.
+    CircularDependency {
+        /// The packages participating in the cycle, in reference order.
+        cycle: Vec<String>,
+    },
+    /// Dependency traversal followed more `Requires:` edges than allowed.
+    MaxDepthExceeded {
+        /// The depth limit that was exceeded.
+        depth: i32,
+    },
+    /// A package was found but its version fails the requested constraint.
+    VersionMismatch {
+        /// The requested package name.
+        name: String,
+        /// The version the located package declares.
+        found: String,
+        /// The requirement the version failed to satisfy.
+        required: String,
+    },
+    /// A `${variable}` reference was never defined (strict mode only).
+    UndefinedVariable {
+        /// The name of the undefined variable.
+        name: String,
+    },
+    /// A variable directly or indirectly references itself.
+    CircularVariableReference {
+        /// The variable at which the cycle was detected.
+        variable: String,
+        /// Every variable involved in the cycle, in reference order.
+        cycle: Vec<String>,
+    },
+    /// An attempt was made to merge a field that must stay unique.
+    MergeDisallowed {
+        /// The field that cannot be merged.
+        keyword: Keyword,
+    },
+    /// A flag field could not be split into shell words.
+    Fragment(crate::fragment::FragmentError),
+    /// The input bytes handed to [`PcFile::from_bytes_no_std`] are not
+    /// valid UTF-8.
+    InvalidUtf8 {
+        /// How many bytes decoded cleanly before the first bad sequence.
+        valid_up_to: usize,
+    },
+    /// A line was neither a comment, a variable assignment nor a field.
+    MalformedLine {
+        /// The file the line came from, when parsing from a path.
+        #[cfg(feature = "std")]
+        path: Option<PathBuf>,
+        /// The 1-based line number where the problem starts.
+        line: usize,
+        /// The offending line content.
+        content: String,
+    },
+}
+
+impl ParseError {
+    /// Attaches the source path to errors that carry a location, so
+    /// diagnostics can be formatted as `<path>:<line>: <message>`.
+    #[cfg(feature = "std")]
+    fn with_path(mut self, source: &Path) -> ParseError {
+        if let ParseError::MalformedLine { path, .. } = &mut self {
+            *path = Some(source.to_path_buf());
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ParseError::Io(err) => write!(f, "i/o error: {err}"),
+            ParseError::VariableExpansionDepthExceeded { variable, depth } => {
+                write!(
+                    f,
+                    "expansion of ${{{variable}}} exceeded the maximum depth of {depth}"
+                )
+            }
+            ParseError::PackageNotFound { name } => {
+                write!(f, "package '{name}' was not found in the search path")
+            }
+            ParseError::CircularDependency { cycle } => {
+                write!(f, "circular dependency detected: {}", cycle.join(" -> "))
+            }
+            ParseError::MaxDepthExceeded { depth } => {
+                write!(f, "dependency traversal exceeded the maximum depth of {depth}")
+            }
+            ParseError::VersionMismatch {
+                name,
+                found,
+                required,
+            } => write!(
+                f,
+                "package '{name}' has version {found}, which does not satisfy '{required}'"
+            ),
+            ParseError::UndefinedVariable { name } => {
+                write!(f, "reference to undefined variable ${{{name}}}")
+            }
+            ParseError::CircularVariableReference { variable, cycle } => {
+                write!(
+                    f,
+                    "circular variable reference involving ${{{variable}}} (cycle: {})",
+                    cycle.join(" -> ")
+                )
+            }
+            ParseError::MergeDisallowed { keyword } => {
+                write!(f, "the {keyword}: field cannot be merged between packages")
+            }
+            ParseError::Fragment(err) => write!(f, "{err}"),
+            ParseError::InvalidUtf8 { valid_up_to } => {
+                write!(f, "input is not valid UTF-8 past byte {valid_up_to}")
+            }
+            #[cfg(feature = "std")]
+            ParseError::MalformedLine {
+                path: Some(path),
+                line,
+                content,
+            } => {
+                write!(f, "{}:{line}: malformed line: {content:?}", path.display())
+            }
+            ParseError::MalformedLine { line, content, .. } => {
+                write!(f, "{line}: malformed line: {content:?}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ParseError::Io(err) => Some(err),
+            ParseError::Fragment(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ParseError {
+    fn from(err: std::io::Error) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+impl From<crate::fragment::FragmentError> for ParseError {
+    fn from(err: crate::fragment::FragmentError) -> Self {
+        ParseError::Fragment(err)
+    }
+}
+
+/// Options controlling `${variable}` expansion.
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// The maximum depth of nested variable references before expansion is
+    /// aborted; matches pkgconf's limit by default.
+    pub max_depth: u32,
+    /// `--define-variable`-style overrides. These shadow file-local
+    /// variables of the same name wherever they are referenced.
+    pub global_vars: HashMap<String, String>,
+    /// When true, references to undefined variables are an error instead
+    /// of silently expanding to the empty string.
+    pub strict: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        ResolveOptions {
+            max_depth: DEFAULT_MAX_EXPANSION_DEPTH,
+            global_vars: HashMap::new(),
+            strict: false,
+        }
+    }
+}
+
+/// The default `${variable}` recursion limit, matching pkgconf.
+pub const DEFAULT_MAX_EXPANSION_DEPTH: u32 = 64;
+
+/// What changed between two versions of a `.pc` file; see [`PcFile::diff`].
+///
+/// "Added" and "removed" are from the perspective of the newer file, and
+/// each changed entry carries the old value before the new one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PcFileDiff {
+    /// Fields present only in the newer file.
+    pub added_fields: Vec<Keyword>,
+    /// Fields present only in the older file.
+    pub removed_fields: Vec<Keyword>,
+    /// Fields whose raw value differs, as `(keyword, old, new)`.
+    pub changed_fields: Vec<(Keyword, String, String)>,
+    /// Variables defined only in the newer file.
+    pub added_variables: Vec<String>,
+    /// Variables defined only in the older file.
+    pub removed_variables: Vec<String>,
+    /// Variables whose value differs, as `(name, old, new)`.
+    pub changed_variables: Vec<(String, String, String)>,
+}
+
+impl PcFileDiff {
+    /// Whether the two files are semantically equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.changed_fields.is_empty()
+            && self.added_variables.is_empty()
+            && self.removed_variables.is_empty()
+            && self.changed_variables.is_empty()
+    }
+
+    /// Whether the change cannot affect consumers' compile or link lines.
+    ///
+    /// Only `Description:` and `URL:` edits qualify; anything touching
+    /// flags, dependencies, the version or a variable (which flag fields
+    /// may expand) is treated as potentially breaking.
+    pub fn is_abi_compatible(&self) -> bool {
+        let harmless = |keyword: &Keyword| {
+            matches!(keyword, Keyword::Description | Keyword::Url)
+        };
+        self.added_fields.iter().all(harmless)
+            && self.removed_fields.iter().all(harmless)
+            && self.changed_fields.iter().all(|(keyword, _, _)| harmless(keyword))
+            && self.added_variables.is_empty()
+            && self.removed_variables.is_empty()
+            && self.changed_variables.is_empty()
+    }
+}
+
+/// A parsed `.pc` file: the keyword fields and the variable definitions,
+/// both kept in their raw (unexpanded) form.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PcFile {
+    /// The path the file was loaded from, if any.
+    #[cfg(feature = "std")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub path: Option<PathBuf>,
+    fields: HashMap<Keyword, String>,
+    // Insertion-ordered so serialization preserves the authored layout.
+    variables: IndexMap<String, String>,
+    // Unrecognized `Key: value` lines, kept verbatim (original casing) so
+    // they survive a to_pc_string round trip.
+    custom_fields: IndexMap<String, String>,
+}
+
+impl PcFile {
+    /// Creates a `.pc` file from scratch with the three required fields.
+    ///
+    /// Optional fields and variables are added with the builder-style
+    /// `with_*` methods:
+    ///
+    /// ```
+    /// use libpkgconf::parser::PcFile;
+    ///
+    /// let pc = PcFile::new("foo", "1.2.3", "The foo library")
+    ///     .with_variable("prefix", "/usr")
+    ///     .with_cflags("-I${prefix}/include")
+    ///     .with_libs("-L${prefix}/lib -lfoo");
+    /// assert!(pc.validate().is_empty());
+    /// ```
+    pub fn new(name: &str, version: &str, description: &str) -> PcFile {
+        let mut pc = PcFile::default();
+        pc.fields.insert(Keyword::Name, name.to_owned());
+        pc.fields.insert(Keyword::Version, version.to_owned());
+        pc.fields
+            .insert(Keyword::Description, description.to_owned());
+        pc
+    }
+
+    /// Sets the `Requires:` field.
+    pub fn with_requires(mut self, deps: &str) -> Self {
+        self.fields.insert(Keyword::Requires, deps.to_owned());
+        self
+    }
+
+    /// Sets the `Cflags:` field.
+    pub fn with_cflags(mut self, flags: &str) -> Self {
+        self.fields.insert(Keyword::Cflags, flags.to_owned());
+        self
+    }
+
+    /// Sets the `Libs:` field.
+    pub fn with_libs(mut self, flags: &str) -> Self {
+        self.fields.insert(Keyword::Libs, flags.to_owned());
+        self
+    }
+
+    /// Sets the `URL:` field.
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.fields.insert(Keyword::Url, url.to_owned());
+        self
+    }
+
+    /// Defines (or overwrites) a variable.
+    pub fn with_variable(mut self, name: &str, value: &str) -> Self {
+        self.variables.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Reads and parses the `.pc` file at `path`.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: &Path) -> Result<PcFile, ParseError> {
+        let content = fs::read_to_string(path)?;
+        let mut pc = Self::parse_str(&content).map_err(|err| err.with_path(path))?;
+        pc.path = Some(path.to_path_buf());
+        Ok(pc)
+    }
+
+    /// The path of the `-uninstalled` counterpart of the `.pc` file at
+    /// `pc_path`, when one exists next to it.
+    ///
+    /// Given `foo.pc` this answers `foo-uninstalled.pc` from the same
+    /// directory, which pkg-config prefers over the installed file; asking
+    /// about a file that is itself an `-uninstalled` variant answers `None`.
+    #[cfg(feature = "std")]
+    pub fn uninstalled_path(pc_path: &Path) -> Option<PathBuf> {
+        let stem = pc_path.file_stem()?.to_str()?;
+        if stem.ends_with("-uninstalled") {
+            return None;
+        }
+        let candidate = pc_path.with_file_name(format!("{stem}-uninstalled.pc"));
+        candidate.is_file().then_some(candidate)
+    }
+
+    /// Reads and parses the `.pc` file at `path` via memory-mapped I/O,
+    /// avoiding the copy that `fs::read_to_string` incurs.
+    ///
+    /// Files that cannot be mapped (e.g. `/proc` filesystem entries) fall
+    /// back to [`PcFile::from_path`].
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap(path: &Path) -> Result<PcFile, ParseError> {
+        let file = fs::File::open(path)?;
+        // SAFETY: the map is dropped before this function returns, and `.pc`
+        // files are not expected to be mutated concurrently during parsing.
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(map) => {
+                let mut pc = Self::from_bytes(&map).map_err(|err| err.with_path(path))?;
+                pc.path = Some(path.to_path_buf());
+                Ok(pc)
+            }
+            Err(_) => Self::from_path(path),
+        }
+    }
+
+    /// Parses `.pc` content from raw bytes, which must be valid UTF-8.
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<PcFile, ParseError> {
+        let content = std::str::from_utf8(bytes).map_err(|err| {
+            ParseError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        })?;
+        Self::parse_str(content)
+    }
+
+    /// Parses `.pc` content from raw bytes without touching any `std`
+    /// machinery, for `no_std` consumers that load file content themselves.
+    ///
+    /// Unlike [`PcFile::from_bytes`] — which is gated behind the `std`
+    /// feature because it reports bad input as an I/O error — invalid UTF-8
+    /// surfaces as [`ParseError::InvalidUtf8`].
+    pub fn from_bytes_no_std(bytes: &[u8]) -> Result<PcFile, ParseError> {
+        let content = core::str::from_utf8(bytes).map_err(|err| ParseError::InvalidUtf8 {
+            valid_up_to: err.valid_up_to(),
+        })?;
+        Self::parse_str(content)
+    }
+
+    /// Parses `.pc` file content from a string.
+    pub(crate) fn parse_str(content: &str) -> Result<PcFile, ParseError> {
+        // Windows tooling sometimes emits a UTF-8 BOM; it must not become
+        // part of the first field or variable name.
+        let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+        let mut pc = PcFile::default();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut i = 0;
+        while i < lines.len() {
+            // Errors report the first line of a continued span.
+            let lineno = i + 1;
+            // A trailing backslash continues the value onto the next line,
+            // with the continuation's leading whitespace stripped. A
+            // backslash anywhere else is preserved literally.
+            let mut logical = Cow::Borrowed(lines[i]);
+            while logical.ends_with('\\') {
+                let joined = logical.to_mut();
+                joined.pop();
+                i += 1;
+                match lines.get(i) {
+                    Some(next) => joined.push_str(next.trim_start()),
+                    None => break,
+                }
+            }
+            i += 1;
+            let line = &*logical;
+            let line = match line.find('#') {
+                Some(idx) => &line[..idx],
+                None => line,
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            // The first of `:` or `=` decides whether this is a field or a
+            // variable assignment, mirroring pkg-config's scanner.
+            let sep = line.find([':', '=']);
+            match sep {
+                Some(idx) if line.as_bytes()[idx] == b'=' => {
+                    let name = line[..idx].trim().to_owned();
+                    let value = line[idx + 1..].trim().to_owned();
+                    pc.variables.insert(name, value);
+                }
+                Some(idx) => {
+                    let name = line[..idx].trim();
+                    let value = line[idx + 1..].trim();
+                    match Keyword::from_str_case_insensitive(name) {
+                        Some(keyword) => {
+                            pc.fields.insert(keyword, value.to_owned());
+                        }
+                        // Non-standard fields are preserved verbatim.
+                        None => {
+                            pc.custom_fields.insert(name.to_owned(), value.to_owned());
+                        }
+                    }
+                }
+                None => {
+                    return Err(ParseError::MalformedLine {
+                        #[cfg(feature = "std")]
+                        path: None,
+                        line: lineno,
+                        content: line.to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(pc)
+    }
+
+    /// Returns the raw (unexpanded) value of `keyword`, if present.
+    pub fn get_field_raw(&self, keyword: Keyword) -> Option<&str> {
+        self.fields.get(&keyword).map(String::as_str)
+    }
+
+    /// The former name of [`PcFile::get_field_raw`].
+    #[deprecated(note = "renamed to `get_field_raw`; use `get_field_expanded` for resolved values")]
+    pub fn get_field(&self, keyword: Keyword) -> Option<&str> {
+        self.get_field_raw(keyword)
+    }
+
+    /// Returns the value of `keyword` with all `${variable}` references
+    /// expanded, `vars` shadowing the file-local definitions, or
+    /// `Ok(None)` when the field is absent.
+    ///
+    /// One call instead of the [`PcFile::get_field_raw`] +
+    /// [`PcFile::resolve_field`] pairing, for callers that always want the
+    /// final value.
+    pub fn get_field_expanded(
+        &self,
+        keyword: Keyword,
+        vars: &HashMap<String, String>,
+    ) -> Result<Option<String>, ParseError> {
+        let options = ResolveOptions {
+            global_vars: vars.clone(),
+            ..ResolveOptions::default()
+        };
+        self.resolve_field_with_options(keyword, &options)
+    }
+
+    /// Iterates over `(name, raw_value)` variable pairs in definition
+    /// order, without expanding `${…}` references.
+    ///
+    /// Feed the file through [`PcFile::resolve_variables`] for the
+    /// expanded values.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The number of variables defined in the file.
+    pub fn variable_count(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Returns every variable name visible to expansion: the file's own
+    /// definitions merged with the keys of `global`, deduplicated and
+    /// sorted.
+    ///
+    /// [`PcFile::variables`] only covers what the file itself defines, so
+    /// names injected from outside — `pc_sysrootdir`, global overrides —
+    /// would be missing from a `--print-variables` style listing built on
+    /// it alone.
+    pub fn all_variable_names(&self, global: &HashMap<String, String>) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .variables
+            .keys()
+            .chain(global.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns the value of a non-standard field, matched by its exact
+    /// (original-casing) name.
+    pub fn get_custom_field(&self, name: &str) -> Option<&str> {
+        self.custom_fields.get(name).map(String::as_str)
+    }
+
+    /// Iterates over `(name, raw_value)` pairs of the non-standard fields
+    /// in file order, mirroring [`PcFile::variables`].
+    pub fn custom_fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.custom_fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the names of all fields present: standard fields in
+    /// canonical order, then custom fields in file order.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        Keyword::CANONICAL_ORDER
+            .iter()
+            .filter(|keyword| self.fields.contains_key(keyword))
+            .map(|keyword| keyword.as_str())
+            .chain(self.custom_fields.keys().map(String::as_str))
+    }
+
+    /// Returns the raw (unexpanded) value of the variable `name`.
+    pub fn get_variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    /// Returns the `Name:` field.
+    pub fn name(&self) -> Option<&str> {
+        self.get_field_raw(Keyword::Name)
+    }
+
+    /// Returns the `Version:` field.
+    pub fn version(&self) -> Option<&str> {
+        self.get_field_raw(Keyword::Version)
+    }
+
+    /// Returns the `Description:` field.
+    pub fn description(&self) -> Option<&str> {
+        self.get_field_raw(Keyword::Description)
+    }
+
+    /// Returns the `URL:` field.
+    pub fn url(&self) -> Option<&str> {
+        self.get_field_raw(Keyword::Url)
+    }
+
+    /// The `Provides:` field parsed into a dependency list, with variables
+    /// expanded. `None` when the field is absent or fails to expand.
+    pub fn get_provides(&self) -> Option<crate::dependency::DependencyList> {
+        let field = self.resolve_field(Keyword::Provides).ok().flatten()?;
+        Some(crate::dependency::DependencyList::parse(&field))
+    }
+
+    /// The `Conflicts:` field parsed into a dependency list, with variables
+    /// expanded. `None` when the field is absent or fails to expand.
+    pub fn get_conflicts(&self) -> Option<crate::dependency::DependencyList> {
+        let field = self.resolve_field(Keyword::Conflicts).ok().flatten()?;
+        Some(crate::dependency::DependencyList::parse(&field))
+    }
+
+    /// Serialises the file back to `.pc` text.
+    ///
+    /// Variables are emitted first in insertion order, then fields in
+    /// [`Keyword::CANONICAL_ORDER`]. Values are emitted verbatim, without
+    /// re-quoting, so a parse/serialise round trip is lossless.
+    pub fn to_pc_string(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.variables {
+            out.push_str(name);
+            out.push('=');
+            out.push_str(value);
+            out.push('\n');
+        }
+        if !self.variables.is_empty() && !self.fields.is_empty() {
+            out.push('\n');
+        }
+        for &keyword in Keyword::CANONICAL_ORDER {
+            if let Some(value) = self.get_field_raw(keyword) {
+                out.push_str(keyword.as_str());
+                out.push_str(": ");
+                out.push_str(value);
+                out.push('\n');
+            }
+        }
+        for (name, value) in &self.custom_fields {
+            out.push_str(name);
+            out.push_str(": ");
+            out.push_str(value);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Appends the raw field value of `keyword` from `other` to this file,
+    /// separated by a comma for the dependency-list fields and a space for
+    /// everything else.
+    ///
+    /// `Name:` and `Version:` identify a package and cannot be merged;
+    /// attempting to fails with [`ParseError::MergeDisallowed`]. Values are
+    /// appended unexpanded, so `${variable}` references in `other` resolve
+    /// against this file's variables afterwards.
+    pub fn merge_field_from(&mut self, keyword: Keyword, other: &PcFile) -> Result<(), ParseError> {
+        let separator = match keyword {
+            Keyword::Name | Keyword::Version => {
+                return Err(ParseError::MergeDisallowed { keyword });
+            }
+            Keyword::Requires
+            | Keyword::RequiresPrivate
+            | Keyword::Conflicts
+            | Keyword::Provides => ", ",
+            _ => " ",
+        };
+        let Some(addition) = other.get_field_raw(keyword) else {
+            return Ok(());
+        };
+        match self.fields.get_mut(&keyword) {
+            Some(existing) if !existing.is_empty() => {
+                existing.push_str(separator);
+                existing.push_str(addition);
+            }
+            _ => {
+                self.fields.insert(keyword, addition.to_owned());
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends `other`'s raw `Cflags:` value to this file's.
+    pub fn merge_cflags_from(&mut self, other: &PcFile) {
+        // Cflags can always be merged, so the error case is unreachable.
+        let _ = self.merge_field_from(Keyword::Cflags, other);
+    }
+
+    /// Appends `other`'s raw `Libs:` value to this file's.
+    pub fn merge_libs_from(&mut self, other: &PcFile) {
+        let _ = self.merge_field_from(Keyword::Libs, other);
+    }
+
+    /// Appends `other`'s raw `Requires:` value to this file's, with a comma
+    /// separator so the combined value stays a valid dependency list.
+    pub fn merge_requires_from(&mut self, other: &PcFile) {
+        let _ = self.merge_field_from(Keyword::Requires, other);
+    }
+
+    /// The differences between this file (the old version) and `other`
+    /// (the new version).
+    ///
+    /// Fields are compared raw (unexpanded) in [`Keyword::CANONICAL_ORDER`];
+    /// variables in `other`'s declaration order. Custom fields and comments
+    /// do not participate.
+    pub fn diff(&self, other: &PcFile) -> PcFileDiff {
+        let mut diff = PcFileDiff::default();
+        for &keyword in Keyword::CANONICAL_ORDER {
+            match (self.get_field_raw(keyword), other.get_field_raw(keyword)) {
+                (None, Some(_)) => diff.added_fields.push(keyword),
+                (Some(_), None) => diff.removed_fields.push(keyword),
+                (Some(old), Some(new)) if old != new => {
+                    diff.changed_fields
+                        .push((keyword, old.to_owned(), new.to_owned()));
+                }
+                _ => {}
+            }
+        }
+        for (name, new) in &other.variables {
+            match self.variables.get(name) {
+                None => diff.added_variables.push(name.clone()),
+                Some(old) if old != new => {
+                    diff.changed_variables
+                        .push((name.clone(), old.clone(), new.clone()));
+                }
+                _ => {}
+            }
+        }
+        for name in self.variables.keys() {
+            if !other.variables.contains_key(name) {
+                diff.removed_variables.push(name.clone());
+            }
+        }
+        diff
+    }
+
+    /// Injects the special `${pc_sysrootdir}` variable, as pkgconf does when
+    /// `PKG_CONFIG_SYSROOT_DIR` is set.
+    ///
+    /// Files that reference it explicitly (`-I${pc_sysrootdir}/usr/include`)
+    /// then expand correctly; pass `None` to reset it to the default `/`.
+    #[cfg(feature = "std")]
+    pub fn set_sysroot_dir(&mut self, sysroot: Option<&Path>) {
+        let value = match sysroot {
+            Some(dir) => dir.display().to_string(),
+            None => "/".to_owned(),
+        };
+        self.variables.insert("pc_sysrootdir".to_owned(), value);
+    }
+
+    /// Expands every variable and returns the fully-resolved variable map.
+    ///
+    /// Fails with [`ParseError::CircularVariableReference`] if any variable
+    /// directly or indirectly references itself.
+    pub fn resolve_variables(&self) -> Result<HashMap<String, String>, ParseError> {
+        self.resolve_variables_with_options(&ResolveOptions::default())
+    }
+
+    /// Resolves every variable with `--define-variable`-style overrides
+    /// applied; each `(name, value)` pair shadows the file-local variable
+    /// of the same name, including in transitively expanded values.
+    pub fn resolve_variables_with_overrides(
+        &self,
+        overrides: &[(&str, &str)],
+    ) -> Result<HashMap<String, String>, ParseError> {
+        let options = ResolveOptions {
+            global_vars: overrides
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..ResolveOptions::default()
+        };
+        self.resolve_variables_with_options(&options)
+    }
+
+    /// Like [`PcFile::resolve_variables`], with explicit [`ResolveOptions`].
+    pub fn resolve_variables_with_options(
+        &self,
+        options: &ResolveOptions,
+    ) -> Result<HashMap<String, String>, ParseError> {
+        self.variables
+            .keys()
+            .map(|name| {
+                // An overridden variable resolves to its override verbatim.
+                let value = match options.global_vars.get(name) {
+                    Some(global) => global.clone(),
+                    None => self.expand_with_options(&self.variables[name], options)?,
+                };
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Returns the value of `keyword` with all `${variable}` references
+    /// expanded, or `Ok(None)` if the field is absent.
+    pub fn resolve_field(&self, keyword: Keyword) -> Result<Option<String>, ParseError> {
+        self.get_field_raw(keyword).map(|raw| self.expand(raw)).transpose()
+    }
+
+    /// Like [`PcFile::resolve_field`], with explicit [`ResolveOptions`].
+    pub fn resolve_field_with_options(
+        &self,
+        keyword: Keyword,
+        options: &ResolveOptions,
+    ) -> Result<Option<String>, ParseError> {
+        self.get_field_raw(keyword)
+            .map(|raw| self.expand_with_options(raw, options))
+            .transpose()
+    }
+
+    /// Like [`PcFile::resolve_field`], but borrows the raw value when it
+    /// contains no `${variable}` references.
+    ///
+    /// Fields without substitutions are the common case (`Libs: -lfoo`),
+    /// and this avoids an allocation per field across a large dependency
+    /// graph.
+    pub fn resolve_field_cow(&self, keyword: Keyword) -> Result<Option<Cow<'_, str>>, ParseError> {
+        match self.get_field_raw(keyword) {
+            None => Ok(None),
+            Some(raw) if !raw.contains("${") => Ok(Some(Cow::Borrowed(raw))),
+            Some(raw) => Ok(Some(Cow::Owned(self.expand(raw)?))),
+        }
+    }
+
+    /// Expands `${variable}` references in `value` against this file's
+    /// variable definitions. References to undefined variables are left
+    /// verbatim.
+    fn expand(&self, value: &str) -> Result<String, ParseError> {
+        self.expand_with_options(value, &ResolveOptions::default())
+    }
+
+    /// Expands `value` honouring the given [`ResolveOptions`].
+    fn expand_with_options(
+        &self,
+        value: &str,
+        options: &ResolveOptions,
+    ) -> Result<String, ParseError> {
+        let mut stack = Vec::new();
+        self.expand_tracked(value, &mut stack, options)
+    }
+
+    /// The recursive worker behind [`PcFile::expand`]; `stack` holds the
+    /// variables currently being expanded so cycles are caught instead of
+    /// recursing forever.
+    fn expand_tracked<'a>(
+        &'a self,
+        value: &str,
+        stack: &mut Vec<&'a str>,
+        options: &'a ResolveOptions,
+    ) -> Result<String, ParseError> {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find('}') {
+                Some(end) => {
+                    let name = &after[..end];
+                    // Global overrides shadow file-local definitions.
+                    let resolved = options
+                        .global_vars
+                        .get_key_value(name)
+                        .or_else(|| self.variables.get_key_value(name));
+                    match resolved {
+                        Some((key, inner)) => {
+                            if let Some(pos) = stack.iter().position(|seen| *seen == name) {
+                                let mut cycle: Vec<String> =
+                                    stack[pos..].iter().map(|s| s.to_string()).collect();
+                                cycle.push(name.to_owned());
+                                return Err(ParseError::CircularVariableReference {
+                                    variable: name.to_owned(),
+                                    cycle,
+                                });
+                            }
+                            if stack.len() as u32 >= options.max_depth {
+                                return Err(ParseError::VariableExpansionDepthExceeded {
+                                    variable: name.to_owned(),
+                                    depth: options.max_depth,
+                                });
+                            }
+                            stack.push(key);
+                            let expanded = self.expand_tracked(inner, stack, options)?;
+                            stack.pop();
+                            out.push_str(&expanded);
+                        }
+                        None if options.strict => {
+                            return Err(ParseError::UndefinedVariable {
+                                name: name.to_owned(),
+                            });
+                        }
+                        // Undefined references expand to nothing, matching
+                        // pkg-config.
+                        None => {}
+                    }
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    // Unterminated reference; emit verbatim.
+                    out.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The file is usable but questionable.
+    Warning,
+    /// The file is semantically incomplete or broken.
+    Error,
+}
+
+/// A single finding from [`PcFile::validate`].
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    /// How serious the issue is.
+    pub severity: Severity,
+    /// A human-readable description of the problem.
+    pub message: String,
+    /// The field the issue refers to, when it concerns a specific field.
+    pub keyword: Option<Keyword>,
+}
+
+impl ValidationIssue {
+    fn error(message: String, keyword: Option<Keyword>) -> ValidationIssue {
+        ValidationIssue {
+            severity: Severity::Error,
+            message,
+            keyword,
+        }
+    }
+
+    fn warning(message: String, keyword: Option<Keyword>) -> ValidationIssue {
+        ValidationIssue {
+            severity: Severity::Warning,
+            message,
+            keyword,
+        }
+    }
+}
+
+impl PcFile {
+    /// Checks the parsed file for semantic problems without performing any
+    /// I/O.
+    ///
+    /// Errors are reported for missing required fields (`Name:`,
+    /// `Version:`) and for `${variable}` references in field values that
+    /// are never defined. Warnings cover a missing or empty
+    /// `Description:`, a file that exports neither `Cflags:` nor `Libs:`,
+    /// and a suspiciously short `Version:`.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for required in [Keyword::Name, Keyword::Version] {
+            if self.get_field_raw(required).is_none_or(str::is_empty) {
+                issues.push(ValidationIssue::error(
+                    format!("missing required field {required}"),
+                    Some(required),
+                ));
+            }
+        }
+        if self.description().is_none_or(str::is_empty) {
+            issues.push(ValidationIssue::warning(
+                "missing or empty Description".to_owned(),
+                Some(Keyword::Description),
+            ));
+        }
+        if self.get_field_raw(Keyword::Cflags).is_none() && self.get_field_raw(Keyword::Libs).is_none() {
+            issues.push(ValidationIssue::warning(
+                "package exports neither Cflags nor Libs".to_owned(),
+                None,
+            ));
+        }
+        if let Some(version) = self.version()
+            && !version.is_empty()
+            && version.len() < 3
+        {
+            issues.push(ValidationIssue::warning(
+                format!("suspiciously short Version {version:?}"),
+                Some(Keyword::Version),
+            ));
+        }
+        for (&keyword, value) in &self.fields {
+            for name in variable_references(value) {
+                if self.get_variable(name).is_none() {
+                    issues.push(ValidationIssue::error(
+                        format!("{keyword} references undefined variable ${{{name}}}"),
+                        Some(keyword),
+                    ));
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Expands `${variable}` references in `raw` against a pre-resolved
+/// variable map (e.g. the output of [`PcFile::resolve_variables`]),
+/// borrowing `raw` when it contains no references.
+///
+/// The map values are substituted as-is — no recursive expansion — so the
+/// cost is a single pass over `raw`. Undefined references expand to the
+/// empty string, matching [`PcFile::resolve_field`].
+pub fn expand_with_map<'a>(raw: &'a str, vars: &HashMap<String, String>) -> Cow<'a, str> {
+    if !raw.contains("${") {
+        return Cow::Borrowed(raw);
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                if let Some(value) = vars.get(name) {
+                    out.push_str(value);
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Expands every variable of `pc` in a single pass, with `global`
+/// overrides shadowing file-local definitions.
+///
+/// Builds the variable reference graph and processes it with Kahn's
+/// algorithm, so each value is expanded exactly once, after everything it
+/// references — regardless of declaration order. Variables left over when
+/// the topological order is exhausted form a cycle, reported as
+/// [`ParseError::CircularVariableReference`]; references to undefined
+/// variables expand to the empty string, matching
+/// [`PcFile::resolve_variables`].
+pub fn resolve_variables_lazy(
+    pc: &PcFile,
+    global: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, ParseError> {
+    // Overridden variables resolve verbatim, so only references between
+    // non-overridden file-local variables constrain the ordering.
+    let mut indegree: IndexMap<&str, usize> =
+        pc.variables.keys().map(|name| (name.as_str(), 0)).collect();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, value) in &pc.variables {
+        if global.contains_key(name) {
+            continue;
+        }
+        for referenced in variable_references(value) {
+            if global.contains_key(referenced) || !pc.variables.contains_key(referenced) {
+                continue;
+            }
+            *indegree.get_mut(name.as_str()).unwrap() += 1;
+            dependents.entry(referenced).or_default().push(name);
+        }
+    }
+    let mut queue: alloc::collections::VecDeque<&str> = indegree
+        .iter()
+        .filter(|&(_, &degree)| degree == 0)
+        .map(|(&name, _)| name)
+        .collect();
+    let mut scope: HashMap<String, String> = global.clone();
+    let mut resolved: HashMap<String, String> = HashMap::new();
+    while let Some(name) = queue.pop_front() {
+        let value = match global.get(name) {
+            Some(value) => value.clone(),
+            None => expand_with_map(&pc.variables[name], &scope).into_owned(),
+        };
+        scope.insert(name.to_owned(), value.clone());
+        resolved.insert(name.to_owned(), value);
+        for dependent in dependents.remove(name).unwrap_or_default() {
+            let degree = indegree.get_mut(dependent).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(dependent);
+            }
+        }
+    }
+    if resolved.len() == pc.variables.len() {
+        return Ok(resolved);
+    }
+    // Everything unprocessed sits on or behind a cycle; walk unresolved
+    // references until one repeats to report it in reference order.
+    let start = indegree
+        .iter()
+        .find(|&(_, &degree)| degree > 0)
+        .map(|(&name, _)| name)
+        .expect("unresolved variables imply a positive indegree");
+    let mut path: Vec<&str> = vec![start];
+    let cycle = loop {
+        let current = *path.last().expect("path starts non-empty");
+        let next = variable_references(&pc.variables[current])
+            .find(|referenced| indegree.get(referenced).is_some_and(|&degree| degree > 0))
+            .expect("a variable on a cycle references another unresolved variable");
+        if let Some(position) = path.iter().position(|&name| name == next) {
+            break &path[position..];
+        }
+        path.push(next);
+    };
+    Err(ParseError::CircularVariableReference {
+        variable: cycle[0].to_owned(),
+        cycle: cycle.iter().map(|&name| name.to_owned()).collect(),
+    })
+}
+
+/// Yields the names referenced as `${name}` in `value`.
+fn variable_references(value: &str) -> impl Iterator<Item = &str> {
+    let mut rest = value;
+    core::iter::from_fn(move || {
+        let start = rest.find("${")?;
+        let after = &rest[start + 2..];
+        let end = after.find('}')?;
+        let name = &after[..end];
+        rest = &after[end + 1..];
+        Some(name)
+    })
+}
+
+impl core::str::FromStr for PcFile {
+    type Err = ParseError;
+
+    /// Parses `.pc` content from an in-memory string:
+    /// `let pc: PcFile = content.parse()?;`
+    fn from_str(s: &str) -> Result<PcFile, ParseError> {
+        Self::parse_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn keyword_matching_is_case_insensitive() {
+        assert_eq!(
+            Keyword::from_str_case_insensitive("name"),
+            Some(Keyword::Name)
+        );
+        assert_eq!(
+            Keyword::from_str_case_insensitive("Name"),
+            Some(Keyword::Name)
+        );
+        assert_eq!(
+            Keyword::from_str_case_insensitive("NAME"),
+            Some(Keyword::Name)
+        );
+        assert_eq!(
+            Keyword::from_str_case_insensitive("Requires.Private"),
+            Some(Keyword::RequiresPrivate)
+        );
+        assert_eq!(Keyword::from_str_case_insensitive("Unknown"), None);
+    }
+
+    #[test]
+    fn parses_capitalized_field_names() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: test\nCFLAGS: -I/opt/include\nlibs: -lfoo\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::Cflags), Some("-I/opt/include"));
+        assert_eq!(pc.get_field_raw(Keyword::Libs), Some("-lfoo"));
+    }
+
+    #[test]
+    fn global_overrides_shadow_file_variables() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nincludedir=${prefix}/include\n\
+             Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I${includedir}\n",
+        )
+        .unwrap();
+        let vars = pc
+            .resolve_variables_with_overrides(&[("prefix", "/opt/cross")])
+            .unwrap();
+        assert_eq!(vars["prefix"], "/opt/cross");
+        // The override is visible through transitive expansion.
+        assert_eq!(vars["includedir"], "/opt/cross/include");
+    }
+
+    // Definition order needs the indexed map, which is std-only.
+    #[cfg(feature = "std")]
+    #[test]
+    fn variables_iterate_raw_values_in_definition_order() {
+        let pc = PcFile::parse_str(
+            "zeta=/z\nprefix=/usr\nincludedir=${prefix}/include\n\
+             Name: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let vars: Vec<(&str, &str)> = pc.variables().collect();
+        assert_eq!(
+            vars,
+            vec![
+                ("zeta", "/z"),
+                ("prefix", "/usr"),
+                ("includedir", "${prefix}/include"),
+            ]
+        );
+        assert_eq!(pc.variable_count(), 3);
+    }
+
+    #[test]
+    fn resolve_field_cow_borrows_without_substitution() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n\
+             Cflags: -I${prefix}/include\nLibs: -lfoo\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            pc.resolve_field_cow(Keyword::Libs).unwrap(),
+            Some(Cow::Borrowed("-lfoo"))
+        ));
+        assert!(matches!(
+            pc.resolve_field_cow(Keyword::Cflags).unwrap(),
+            Some(Cow::Owned(ref s)) if s == "-I/usr/include"
+        ));
+        assert_eq!(pc.resolve_field_cow(Keyword::LibsPrivate).unwrap(), None);
+    }
+
+    #[test]
+    fn expand_with_map_borrows_and_substitutes() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let vars = pc.resolve_variables().unwrap();
+        assert!(matches!(expand_with_map("-lfoo", &vars), Cow::Borrowed("-lfoo")));
+        assert_eq!(expand_with_map("-I${prefix}/include", &vars), "-I/usr/include");
+        assert_eq!(expand_with_map("-I${nope}", &vars), "-I");
+    }
+
+    #[test]
+    fn builder_produces_a_valid_round_trippable_file() {
+        let pc = PcFile::new("foo", "1.2.3", "The foo library")
+            .with_variable("prefix", "/usr")
+            .with_variable("includedir", "${prefix}/include")
+            .with_url("https://example.com/foo")
+            .with_requires("bar >= 1.0")
+            .with_cflags("-I${includedir}")
+            .with_libs("-L${prefix}/lib -lfoo");
+        assert!(pc.validate().is_empty());
+        let reparsed = PcFile::parse_str(&pc.to_pc_string()).unwrap();
+        assert_eq!(reparsed.name(), Some("foo"));
+        assert_eq!(reparsed.url(), Some("https://example.com/foo"));
+        assert_eq!(
+            reparsed.resolve_field(Keyword::Cflags).unwrap().as_deref(),
+            Some("-I/usr/include")
+        );
+    }
+
+    #[test]
+    fn provides_field_parses_into_a_dependency_list() {
+        let pc = PcFile::parse_str(
+            "Name: openssl\nVersion: 3.0\nDescription: d\nProvides: virtual-ssl = ${ver}\nver=3.0\n",
+        )
+        .unwrap();
+        let provides = pc.get_provides().unwrap();
+        assert_eq!(provides.len(), 1);
+        assert_eq!(provides.get("virtual-ssl").unwrap().version.as_deref(), Some("3.0"));
+        assert!(PcFile::parse_str("Name: a\nVersion: 1\nDescription: d\n")
+            .unwrap()
+            .get_provides()
+            .is_none());
+    }
+
+    #[test]
+    fn parses_url_field_and_url_variable_independently() {
+        let pc = PcFile::parse_str(
+            "url=https://variable.example.com/
+Name: foo
+Version: 1.0
+Description: d
+             URL: https://example.com/
+",
+        )
+        .unwrap();
+        assert_eq!(pc.url(), Some("https://example.com/"));
+        assert_eq!(pc.get_variable("url"), Some("https://variable.example.com/"));
+        // URL is emitted after Version in canonical order.
+        let emitted = pc.to_pc_string();
+        assert!(emitted.find("Version:").unwrap() < emitted.find("URL:").unwrap());
+    }
+
+    #[test]
+    fn parses_private_fields() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nRequires.private: zlib\n\
+             Cflags.private: -DSTATIC\nLibs.private: -lm -lpthread\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::RequiresPrivate), Some("zlib"));
+        assert_eq!(pc.get_field_raw(Keyword::CflagsPrivate), Some("-DSTATIC"));
+        assert_eq!(pc.get_field_raw(Keyword::LibsPrivate), Some("-lm -lpthread"));
+        let reparsed = PcFile::parse_str(&pc.to_pc_string()).unwrap();
+        assert_eq!(reparsed.get_field_raw(Keyword::LibsPrivate), Some("-lm -lpthread"));
+    }
+
+    #[test]
+    fn parses_every_field_name_in_all_lowercase() {
+        let pc = PcFile::parse_str(
+            "name: foo\ndescription: d\nversion: 1.0\nrequires: bar\n\
+             requires.private: baz\ncflags: -I/x\nlibs: -lfoo\n",
+        )
+        .unwrap();
+        for keyword in [
+            Keyword::Name,
+            Keyword::Description,
+            Keyword::Version,
+            Keyword::Requires,
+            Keyword::RequiresPrivate,
+            Keyword::Cflags,
+            Keyword::Libs,
+        ] {
+            assert!(
+                pc.get_field_raw(keyword).is_some(),
+                "lowercase field for {keyword} was not recognized"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_variables_and_expands_fields() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nincludedir=${prefix}/include\n\
+             Name: foo\nVersion: 1.2.3\nDescription: test\n\
+             Cflags: -I${includedir}\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_variable("includedir"), Some("${prefix}/include"));
+        assert_eq!(
+            pc.resolve_field(Keyword::Cflags).unwrap().as_deref(),
+            Some("-I/usr/include")
+        );
+        let vars = pc.resolve_variables().unwrap();
+        assert_eq!(vars["includedir"], "/usr/include");
+    }
+
+    #[test]
+    fn undefined_variable_references_expand_to_nothing_by_default() {
+        let pc = PcFile::parse_str("Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I${nope}\n")
+            .unwrap();
+        assert_eq!(pc.resolve_field(Keyword::Cflags).unwrap().as_deref(), Some("-I"));
+    }
+
+    #[test]
+    fn strict_mode_rejects_undefined_variable_references() {
+        let pc = PcFile::parse_str(
+            "dir=${nope}/include\nName: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let options = ResolveOptions {
+            strict: true,
+            ..ResolveOptions::default()
+        };
+        let err = pc.resolve_variables_with_options(&options).unwrap_err();
+        assert!(matches!(err, ParseError::UndefinedVariable { ref name } if name == "nope"));
+    }
+
+    #[test]
+    fn globally_defined_variables_resolve_in_strict_mode() {
+        let pc = PcFile::parse_str(
+            "dir=${external}/include\nName: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let options = ResolveOptions {
+            strict: true,
+            global_vars: HashMap::from([("external".to_owned(), "/opt".to_owned())]),
+            ..ResolveOptions::default()
+        };
+        let vars = pc.resolve_variables_with_options(&options).unwrap();
+        assert_eq!(vars["dir"], "/opt/include");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn pc_sysrootdir_expands_when_injected() {
+        let mut pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I${pc_sysrootdir}/usr/include\n",
+        )
+        .unwrap();
+        pc.set_sysroot_dir(Some(Path::new("/sysroot")));
+        assert_eq!(
+            pc.resolve_field(Keyword::Cflags).unwrap().as_deref(),
+            Some("-I/sysroot/usr/include")
+        );
+        pc.set_sysroot_dir(None);
+        assert_eq!(
+            pc.resolve_field(Keyword::Cflags).unwrap().as_deref(),
+            Some("-I//usr/include")
+        );
+    }
+
+    #[test]
+    fn get_field_expanded_resolves_in_one_call() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n\
+             Cflags: -I${prefix}/include\n",
+        )
+        .unwrap();
+        assert_eq!(
+            pc.get_field_raw(Keyword::Cflags),
+            Some("-I${prefix}/include")
+        );
+        assert_eq!(
+            pc.get_field_expanded(Keyword::Cflags, &HashMap::new()).unwrap(),
+            Some("-I/usr/include".to_owned())
+        );
+        let vars = HashMap::from([("prefix".to_owned(), "/opt".to_owned())]);
+        assert_eq!(
+            pc.get_field_expanded(Keyword::Cflags, &vars).unwrap(),
+            Some("-I/opt/include".to_owned())
+        );
+        // An absent field is not an error.
+        assert_eq!(pc.get_field_expanded(Keyword::Libs, &vars).unwrap(), None);
+    }
+
+    #[test]
+    fn all_variable_names_includes_injected_globals() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nlibdir=${prefix}/lib\nName: x\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let global: HashMap<String, String> = [
+            ("pc_sysrootdir".to_owned(), "/sysroot".to_owned()),
+            ("prefix".to_owned(), "/opt".to_owned()),
+        ]
+        .into();
+        assert_eq!(
+            pc.all_variable_names(&global),
+            ["libdir", "pc_sysrootdir", "prefix"]
+        );
+        assert_eq!(pc.all_variable_names(&HashMap::new()), ["libdir", "prefix"]);
+    }
+
+    #[test]
+    fn direct_variable_self_reference_is_an_error() {
+        let pc = PcFile::parse_str("foo=${foo}bar\nName: x\nVersion: 1.0\nDescription: d\n")
+            .unwrap();
+        let err = pc.resolve_variables().unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::CircularVariableReference { ref variable, .. } if variable == "foo"
+        ));
+    }
+
+    #[test]
+    fn indirect_variable_cycle_is_an_error() {
+        let pc = PcFile::parse_str(
+            "a=${b}\nb=${a}\nName: x\nVersion: 1.0\nDescription: d\nCflags: -I${a}\n",
+        )
+        .unwrap();
+        let err = pc.resolve_field(Keyword::Cflags).unwrap_err();
+        let rendered = err.to_string();
+        assert!(rendered.contains('a') && rendered.contains("->"), "{rendered}");
+    }
+
+    fn chain(depth: usize) -> PcFile {
+        let mut content = String::from("v0=/base\n");
+        for i in 1..=depth {
+            content.push_str(&format!("v{i}=${{v{}}}/x\n", i - 1));
+        }
+        content.push_str("Name: x\nVersion: 1.0\nDescription: d\n");
+        PcFile::parse_str(&content).unwrap()
+    }
+
+    #[test]
+    fn deep_chains_exceed_the_default_depth_limit() {
+        let err = chain(70).resolve_variables().unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::VariableExpansionDepthExceeded { depth: 64, .. }
+        ));
+    }
+
+    #[test]
+    fn chains_below_the_limit_resolve() {
+        let vars = chain(63).resolve_variables().unwrap();
+        assert!(vars["v63"].starts_with("/base/x"));
+    }
+
+    #[test]
+    fn depth_limit_is_configurable() {
+        let options = ResolveOptions {
+            max_depth: 4,
+            ..ResolveOptions::default()
+        };
+        assert!(chain(3).resolve_variables_with_options(&options).is_ok());
+        assert!(chain(10).resolve_variables_with_options(&options).is_err());
+    }
+
+    #[test]
+    fn non_cyclic_chains_still_resolve() {
+        let pc = PcFile::parse_str(
+            "a=${b}/x\nb=${c}/y\nc=/base\nName: x\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let vars = pc.resolve_variables().unwrap();
+        assert_eq!(vars["a"], "/base/y/x");
+    }
+
+    #[test]
+    fn lazy_expansion_agrees_with_the_recursive_resolver() {
+        let pc = PcFile::parse_str(
+            "a=${b}/x\nb=${c}/y\nc=/base\nName: x\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let global = HashMap::new();
+        let lazy = resolve_variables_lazy(&pc, &global).unwrap();
+        assert_eq!(lazy, pc.resolve_variables().unwrap());
+        assert_eq!(lazy["a"], "/base/y/x");
+    }
+
+    #[test]
+    fn lazy_expansion_handles_chains_beyond_the_recursion_limit() {
+        // The recursive resolver caps nesting at 64; the topological pass
+        // expands each variable exactly once and has no such limit.
+        let vars = resolve_variables_lazy(&chain(200), &HashMap::new()).unwrap();
+        assert!(vars["v200"].starts_with("/base/x"));
+        assert!(vars["v200"].ends_with("/x"));
+    }
+
+    #[test]
+    fn lazy_expansion_lets_globals_shadow_and_break_cycles() {
+        let pc = PcFile::parse_str(
+            "a=${b}/x\nb=${a}/y\nName: x\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let global: HashMap<String, String> =
+            [("b".to_owned(), "/override".to_owned())].into();
+        let vars = resolve_variables_lazy(&pc, &global).unwrap();
+        assert_eq!(vars["b"], "/override");
+        assert_eq!(vars["a"], "/override/x");
+    }
+
+    #[test]
+    fn lazy_expansion_reports_cycles_in_reference_order() {
+        let pc = PcFile::parse_str(
+            "head=${a}\na=${b}/x\nb=${c}/y\nc=${a}/z\nName: x\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let err = resolve_variables_lazy(&pc, &HashMap::new()).unwrap_err();
+        match err {
+            ParseError::CircularVariableReference { variable, cycle } => {
+                assert_eq!(cycle, ["a", "b", "c"]);
+                assert_eq!(variable, "a");
+            }
+            other => panic!("expected a cycle error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn custom_fields_are_preserved() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nFoo-bar-baz: custom value\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_custom_field("Foo-bar-baz"), Some("custom value"));
+        assert_eq!(pc.get_custom_field("unknown"), None);
+        let names: Vec<&str> = pc.field_names().collect();
+        assert_eq!(names, vec!["Name", "Description", "Version", "Foo-bar-baz"]);
+    }
+
+    #[test]
+    fn custom_fields_survive_round_trip() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nFoo-bar-baz: custom value\n",
+        )
+        .unwrap();
+        let reparsed = PcFile::parse_str(&pc.to_pc_string()).unwrap();
+        assert_eq!(reparsed.get_custom_field("Foo-bar-baz"), Some("custom value"));
+    }
+
+    #[test]
+    fn to_pc_string_round_trips() {
+        let source = "prefix=/usr\nexec_prefix=${prefix}\nlibdir=${exec_prefix}/lib\n\
+                      Name: foo\nDescription: a library\nVersion: 1.2.3\n\
+                      Requires: bar >= 1.0\nCflags: -I${prefix}/include \"-DQUOTED=a b\"\n\
+                      Libs: -L${libdir} -lfoo\n";
+        let pc = PcFile::parse_str(source).unwrap();
+        let emitted = pc.to_pc_string();
+        let reparsed = PcFile::parse_str(&emitted).unwrap();
+        for &keyword in Keyword::CANONICAL_ORDER {
+            assert_eq!(reparsed.get_field_raw(keyword), pc.get_field_raw(keyword), "{keyword}");
+        }
+        assert_eq!(
+            reparsed.resolve_variables().unwrap(),
+            pc.resolve_variables().unwrap()
+        );
+    }
+
+    // Definition order needs the indexed map, which is std-only.
+    #[cfg(feature = "std")]
+    #[test]
+    fn to_pc_string_emits_variables_in_insertion_order() {
+        let pc = PcFile::parse_str("zeta=/z\nalpha=/a\nName: x\nVersion: 1\nDescription: d\n")
+            .unwrap();
+        let emitted = pc.to_pc_string();
+        let zeta = emitted.find("zeta=").unwrap();
+        let alpha = emitted.find("alpha=").unwrap();
+        assert!(zeta < alpha, "variable order not preserved:\n{emitted}");
+    }
+
+    #[test]
+    fn utf8_bom_is_stripped() {
+        let without = "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n";
+        let with_bom = format!("\u{FEFF}{without}");
+        let plain = PcFile::parse_str(without).unwrap();
+        let bommed = PcFile::parse_str(&with_bom).unwrap();
+        assert_eq!(bommed.name(), plain.name());
+        assert_eq!(bommed.get_variable("prefix"), plain.get_variable("prefix"));
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let pc = PcFile::parse_str("# header\n\nName: foo # trailing\nVersion: 1.0\nDescription: d\n")
+            .unwrap();
+        assert_eq!(pc.name(), Some("foo"));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap_parse_matches_buffered_parse() {
+        let path = std::env::temp_dir().join("libpkgconf-mmap-test.pc");
+        fs::write(&path, "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n").unwrap();
+        let buffered = PcFile::from_path(&path).unwrap();
+        let mapped = PcFile::from_path_mmap(&path).unwrap();
+        assert_eq!(buffered.name(), mapped.name());
+        assert_eq!(buffered.get_variable("prefix"), mapped.get_variable("prefix"));
+        assert_eq!(buffered.path, mapped.path);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_str_and_from_bytes_agree() {
+        let content = "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\n";
+        let from_str: PcFile = content.parse().unwrap();
+        let from_bytes = PcFile::from_bytes(content.as_bytes()).unwrap();
+        assert_eq!(from_str.name(), from_bytes.name());
+        assert_eq!(from_str.get_variable("prefix"), from_bytes.get_variable("prefix"));
+    }
+
+    #[test]
+    fn from_bytes_no_std_parses_and_reports_bad_utf8() {
+        let pc =
+            PcFile::from_bytes_no_std(b"Name: foo\nVersion: 1.0\nDescription: d\n").unwrap();
+        assert_eq!(pc.name(), Some("foo"));
+        let err = PcFile::from_bytes_no_std(b"Name: f\xffoo\n").unwrap_err();
+        assert!(matches!(err, ParseError::InvalidUtf8 { valid_up_to: 7 }));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_bytes_rejects_invalid_utf8() {
+        let err = PcFile::from_bytes(b"Name: \xff\xfe\n").unwrap_err();
+        assert!(matches!(err, ParseError::Io(_)));
+    }
+
+    #[test]
+    fn backslash_continuation_joins_lines() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I/a \\\n    -I/b\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::Cflags), Some("-I/a -I/b"));
+    }
+
+    #[test]
+    fn continuation_spans_three_or_more_lines() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nLibs: -L/lib \\\n  -lfoo \\\n  -lbar\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::Libs), Some("-L/lib -lfoo -lbar"));
+    }
+
+    #[test]
+    fn continuation_at_end_of_file_drops_the_backslash() {
+        let pc = PcFile::parse_str("Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I/a \\")
+            .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::Cflags), Some("-I/a"));
+    }
+
+    #[test]
+    fn backslash_not_at_end_of_line_is_preserved() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I/opt/My\\ Lib/include\n",
+        )
+        .unwrap();
+        assert_eq!(pc.get_field_raw(Keyword::Cflags), Some("-I/opt/My\\ Lib/include"));
+    }
+
+    #[test]
+    fn error_in_continued_span_reports_the_first_line() {
+        let err =
+            PcFile::parse_str("Name: foo\nVersion: 1.0\njunk \\\n continued junk\n").unwrap_err();
+        assert!(matches!(err, ParseError::MalformedLine { line: 3, .. }));
+    }
+
+    #[test]
+    fn validate_accepts_a_complete_file() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: a library\nCflags: -I${prefix}/include\n",
+        )
+        .unwrap();
+        assert!(pc.validate().is_empty());
+    }
+
+    #[test]
+    fn validate_flags_missing_required_fields() {
+        let pc = PcFile::parse_str("Description: d\nCflags: -I/x\n").unwrap();
+        let issues = pc.validate();
+        let errors: Vec<_> = issues
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|i| i.keyword == Some(Keyword::Name)));
+        assert!(errors.iter().any(|i| i.keyword == Some(Keyword::Version)));
+    }
+
+    #[test]
+    fn validate_flags_undefined_variable_references() {
+        let pc = PcFile::parse_str(
+            "Name: foo\nVersion: 1.0\nDescription: d\nCflags: -I${missing}/include\n",
+        )
+        .unwrap();
+        let issues = pc.validate();
+        assert!(issues.iter().any(|i| {
+            i.severity == Severity::Error && i.message.contains("${missing}")
+        }));
+    }
+
+    #[test]
+    fn validate_warns_about_flagless_and_short_version() {
+        let pc = PcFile::parse_str("Name: foo\nVersion: 1\nDescription: d\n").unwrap();
+        let issues = pc.validate();
+        assert!(issues.iter().all(|i| i.severity == Severity::Warning));
+        assert!(issues.iter().any(|i| i.message.contains("neither Cflags nor Libs")));
+        assert!(issues.iter().any(|i| i.message.contains("short Version")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_round_trip() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\nCflags: -I${prefix}/include\n",
+        )
+        .unwrap();
+        let json = serde_json::to_string(&pc).unwrap();
+        assert!(json.contains("\"cflags\""), "keywords serialize lowercase: {json}");
+        let back: PcFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.name(), pc.name());
+        assert_eq!(
+            back.resolve_field(Keyword::Cflags).unwrap(),
+            pc.resolve_field(Keyword::Cflags).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_toml_round_trip() {
+        let pc = PcFile::parse_str(
+            "prefix=/usr\nName: foo\nVersion: 1.0\nDescription: d\nLibs: -L${prefix}/lib -lfoo\n",
+        )
+        .unwrap();
+        let toml = toml::to_string(&pc).unwrap();
+        let back: PcFile = toml::from_str(&toml).unwrap();
+        assert_eq!(back.version(), pc.version());
+        assert_eq!(
+            back.resolve_field(Keyword::Libs).unwrap(),
+            pc.resolve_field(Keyword::Libs).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn unknown_keyword_fails_to_deserialize() {
+        let err = serde_json::from_str::<Keyword>("\"not-a-field\"").unwrap_err();
+        assert!(err.to_string().contains("unknown pkg-config field name"));
+    }
+
+    #[test]
+    fn malformed_line_is_an_error() {
+        let err = PcFile::parse_str("this is not a pc line\n").unwrap_err();
+        assert!(matches!(err, ParseError::MalformedLine { line: 1, .. }));
+    }
+
+    #[test]
+    fn malformed_line_reports_its_line_number() {
+        let err = PcFile::parse_str("Name: foo\nVersion: 1.0\njunk\n").unwrap_err();
+        assert!(matches!(err, ParseError::MalformedLine { line: 3, .. }));
+        assert!(err.to_string().starts_with("3: "));
+    }
+
+    #[test]
+    fn crlf_line_endings_do_not_skew_line_numbers() {
+        let err = PcFile::parse_str("Name: foo\r\nVersion: 1.0\r\njunk\r\n").unwrap_err();
+        assert!(matches!(err, ParseError::MalformedLine { line: 3, .. }));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn uninstalled_path_finds_the_counterpart_next_door() {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-uninstalled-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let installed = dir.join("foo.pc");
+        fs::write(&installed, "Name: foo\nVersion: 1.0\nDescription: d\n").unwrap();
+        assert_eq!(PcFile::uninstalled_path(&installed), None);
+        let uninstalled = dir.join("foo-uninstalled.pc");
+        fs::write(&uninstalled, "Name: foo\nVersion: 1.0\nDescription: d\n").unwrap();
+        assert_eq!(PcFile::uninstalled_path(&installed), Some(uninstalled.clone()));
+        // The -uninstalled file has no counterpart of its own.
+        assert_eq!(PcFile::uninstalled_path(&uninstalled), None);
+    }
+
+    #[test]
+    fn diff_of_identical_files_is_empty() {
+        let pc = PcFile::new("foo", "1.0", "d")
+            .with_variable("prefix", "/usr")
+            .with_cflags("-I${prefix}/include");
+        let diff = pc.diff(&pc.clone());
+        assert!(diff.is_empty());
+        assert!(diff.is_abi_compatible());
+    }
+
+    #[test]
+    fn diff_reports_each_category_of_field_change() {
+        let old = PcFile::new("foo", "1.0", "d").with_cflags("-I/usr/include/foo");
+        let new = PcFile::new("foo", "1.1", "d")
+            .with_libs("-lfoo")
+            .with_url("https://example.invalid/foo");
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_fields, [Keyword::Url, Keyword::Libs]);
+        assert_eq!(diff.removed_fields, [Keyword::Cflags]);
+        assert_eq!(
+            diff.changed_fields,
+            [(Keyword::Version, "1.0".to_owned(), "1.1".to_owned())]
+        );
+        assert!(!diff.is_empty());
+        assert!(!diff.is_abi_compatible());
+    }
+
+    #[test]
+    fn diff_reports_each_category_of_variable_change() {
+        let old = PcFile::new("foo", "1.0", "d")
+            .with_variable("prefix", "/usr")
+            .with_variable("libdir", "${prefix}/lib");
+        let new = PcFile::new("foo", "1.0", "d")
+            .with_variable("prefix", "/opt/foo")
+            .with_variable("includedir", "${prefix}/include");
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_variables, ["includedir"]);
+        assert_eq!(diff.removed_variables, ["libdir"]);
+        assert_eq!(
+            diff.changed_variables,
+            [("prefix".to_owned(), "/usr".to_owned(), "/opt/foo".to_owned())]
+        );
+        // A variable may feed flag expansion, so this is not ABI-safe.
+        assert!(!diff.is_abi_compatible());
+    }
+
+    #[test]
+    fn description_and_url_edits_are_abi_compatible() {
+        let old = PcFile::new("foo", "1.0", "old words");
+        let new = PcFile::new("foo", "1.0", "new words")
+            .with_url("https://example.invalid/foo");
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert!(diff.is_abi_compatible());
+    }
+
+    #[test]
+    fn merging_appends_flag_fields_with_a_space() {
+        let mut pc = PcFile::new("combined", "1.0", "d")
+            .with_cflags("-I/opt/a/include")
+            .with_libs("-L/opt/a/lib -la");
+        let other = PcFile::new("b", "2.0", "d")
+            .with_cflags("-I/opt/b/include")
+            .with_libs("-lb");
+        pc.merge_cflags_from(&other);
+        pc.merge_libs_from(&other);
+        assert_eq!(
+            pc.get_field_raw(Keyword::Cflags),
+            Some("-I/opt/a/include -I/opt/b/include")
+        );
+        assert_eq!(pc.get_field_raw(Keyword::Libs), Some("-L/opt/a/lib -la -lb"));
+    }
+
+    #[test]
+    fn merging_requires_uses_a_comma_separator() {
+        let mut pc = PcFile::new("combined", "1.0", "d").with_requires("base >= 1.0");
+        let other = PcFile::new("b", "2.0", "d").with_requires("extra");
+        pc.merge_requires_from(&other);
+        assert_eq!(pc.get_field_raw(Keyword::Requires), Some("base >= 1.0, extra"));
+    }
+
+    #[test]
+    fn merging_into_an_absent_field_adopts_the_other_value() {
+        let mut pc = PcFile::new("combined", "1.0", "d");
+        let other = PcFile::new("b", "2.0", "d").with_cflags("-DB");
+        pc.merge_cflags_from(&other);
+        assert_eq!(pc.get_field_raw(Keyword::Cflags), Some("-DB"));
+        // A merge source without the field leaves ours untouched.
+        pc.merge_libs_from(&other);
+        assert_eq!(pc.get_field_raw(Keyword::Libs), None);
+    }
+
+    #[test]
+    fn merging_name_and_version_is_disallowed() {
+        let mut pc = PcFile::new("combined", "1.0", "d");
+        let other = PcFile::new("b", "2.0", "d");
+        for keyword in [Keyword::Name, Keyword::Version] {
+            let err = pc.merge_field_from(keyword, &other).unwrap_err();
+            assert!(matches!(err, ParseError::MergeDisallowed { keyword: k } if k == keyword));
+        }
+        assert_eq!(pc.name(), Some("combined"));
+        assert_eq!(pc.version(), Some("1.0"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn errors_from_a_path_are_formatted_as_path_line_message() {
+        let path = std::env::temp_dir().join("libpkgconf-lineno-test.pc");
+        fs::write(&path, "Name: foo\nbroken line\n").unwrap();
+        let err = PcFile::from_path(&path).unwrap_err();
+        let rendered = err.to_string();
+        assert!(
+            rendered.starts_with(&format!("{}:2: ", path.display())),
+            "unexpected diagnostic: {rendered}"
+        );
+    }
+}
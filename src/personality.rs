@@ -0,0 +1,366 @@
+//! Cross-compilation personalities.
+//!
+//! A personality bundles the toolchain-specific settings pkgconf needs when
+//! cross-compiling: the target triplet, the sysroot, and the search paths
+//! for `.pc` files and system directories. Two on-disk formats are
+//! supported: pkgconf's INI-style `.personality` files and a TOML
+//! representation that is easier to generate from Rust tooling.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories scanned for `<triplet>.personality` files, in priority
+/// order, mirroring pkgconf's `personality.d` layout.
+pub const DEFAULT_PERSONALITY_PATH: &[&str] = &[
+    "/usr/share/pkgconfig/personality.d",
+    "/etc/pkgconfig/personality.d",
+];
+
+/// A cross-compilation personality.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Personality {
+    /// The target triplet, e.g. `aarch64-linux-gnu`.
+    pub triplet: String,
+    /// The sysroot directory prepended to paths from resolved `.pc` files.
+    pub sysroot_dir: Option<PathBuf>,
+    /// Directories searched for `.pc` files.
+    pub pkg_config_path: Vec<PathBuf>,
+    /// Library directories considered part of the system and filtered from
+    /// `-L` output.
+    pub system_libdirs: Vec<PathBuf>,
+    /// Include directories considered part of the system and filtered from
+    /// `-I` output.
+    pub system_includedirs: Vec<PathBuf>,
+}
+
+/// An error produced while reading or parsing a personality file.
+#[derive(Debug)]
+pub enum PersonalityError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// A line or value did not conform to the expected format.
+    Malformed(String),
+    /// The file extension does not identify a known personality format.
+    UnknownFormat(PathBuf),
+}
+
+impl fmt::Display for PersonalityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersonalityError::Io(err) => write!(f, "i/o error: {err}"),
+            PersonalityError::Malformed(detail) => {
+                write!(f, "malformed personality file: {detail}")
+            }
+            PersonalityError::UnknownFormat(path) => {
+                write!(f, "unknown personality format: {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersonalityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersonalityError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PersonalityError {
+    fn from(err: std::io::Error) -> Self {
+        PersonalityError::Io(err)
+    }
+}
+
+impl Personality {
+    /// Reads a personality from `path`, choosing the parser by extension:
+    /// `.personality` is parsed as INI, `.toml` as TOML.
+    pub fn from_file(path: &Path) -> Result<Personality, PersonalityError> {
+        let parse = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("personality") => Personality::from_ini,
+            Some("toml") => Personality::from_toml,
+            _ => return Err(PersonalityError::UnknownFormat(path.to_path_buf())),
+        };
+        parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a pkgconf INI-style personality, e.g.:
+    ///
+    /// ```text
+    /// Triplet: aarch64-linux-gnu
+    /// SysrootDir: /sysroot
+    /// DefaultSearchPaths: /sysroot/usr/lib/pkgconfig
+    /// ```
+    pub fn from_ini(content: &str) -> Result<Personality, PersonalityError> {
+        let mut personality = Personality::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(PersonalityError::Malformed(line.to_owned()));
+            };
+            let value = value.trim();
+            match key.trim() {
+                k if k.eq_ignore_ascii_case("Triplet") => personality.triplet = value.to_owned(),
+                k if k.eq_ignore_ascii_case("SysrootDir") => {
+                    personality.sysroot_dir = Some(PathBuf::from(value));
+                }
+                k if k.eq_ignore_ascii_case("DefaultSearchPaths") => {
+                    personality.pkg_config_path = split_path_list(value);
+                }
+                k if k.eq_ignore_ascii_case("SystemLibraryPaths") => {
+                    personality.system_libdirs = split_path_list(value);
+                }
+                k if k.eq_ignore_ascii_case("SystemIncludePaths") => {
+                    personality.system_includedirs = split_path_list(value);
+                }
+                // Unknown keys are ignored for forward compatibility.
+                _ => {}
+            }
+        }
+        Ok(personality)
+    }
+
+    /// Parses the TOML personality representation:
+    ///
+    /// ```text
+    /// [personality]
+    /// triplet = "aarch64-linux-gnu"
+    /// sysroot = "/sysroot"
+    /// pkg_config_path = ["/sysroot/usr/lib/pkgconfig"]
+    /// ```
+    ///
+    /// Only the flat subset of TOML used by personality files is accepted:
+    /// string and string-array values under a `[personality]` table.
+    pub fn from_toml(content: &str) -> Result<Personality, PersonalityError> {
+        let mut personality = Personality::default();
+        let mut in_table = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(table) = line.strip_prefix('[') {
+                let Some(name) = table.strip_suffix(']') else {
+                    return Err(PersonalityError::Malformed(line.to_owned()));
+                };
+                in_table = name.trim() == "personality";
+                continue;
+            }
+            if !in_table {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(PersonalityError::Malformed(line.to_owned()));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "triplet" => personality.triplet = toml_string(value)?,
+                "sysroot" => personality.sysroot_dir = Some(PathBuf::from(toml_string(value)?)),
+                "pkg_config_path" => personality.pkg_config_path = toml_path_array(value)?,
+                "system_libdirs" => personality.system_libdirs = toml_path_array(value)?,
+                "system_includedirs" => personality.system_includedirs = toml_path_array(value)?,
+                _ => {}
+            }
+        }
+        Ok(personality)
+    }
+
+    /// The personality describing the host platform, for non-cross use.
+    ///
+    /// Mirrors the crate-level defaults ([`crate::DEFAULT_PKGCONFIG_PATH`]
+    /// and friends), which select Unix, MinGW/MSYS2 or empty path sets at
+    /// compile time. Registry-provided paths are not consulted.
+    pub fn default_for_host() -> Personality {
+        Personality {
+            triplet: format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+            sysroot_dir: None,
+            pkg_config_path: crate::DEFAULT_PKGCONFIG_PATH
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            system_libdirs: crate::DEFAULT_SYSTEM_LIBDIRS
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            system_includedirs: crate::DEFAULT_SYSTEM_INCLUDEDIRS
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+        }
+    }
+
+    /// Looks up the personality for a target triplet in the standard
+    /// `personality.d` directories, trying `<triplet>.personality` first
+    /// and `<triplet>.toml` second in each.
+    pub fn for_triple(triple: &str) -> Option<Personality> {
+        let dirs: Vec<PathBuf> = DEFAULT_PERSONALITY_PATH.iter().map(PathBuf::from).collect();
+        Personality::for_triple_in(triple, &dirs)
+    }
+
+    /// Like [`Personality::for_triple`], with an explicit directory list.
+    pub fn for_triple_in(triple: &str, dirs: &[PathBuf]) -> Option<Personality> {
+        for dir in dirs {
+            for file in [
+                format!("{triple}.personality"),
+                format!("{triple}.toml"),
+            ] {
+                let path = dir.join(file);
+                if path.is_file()
+                    && let Ok(personality) = Personality::from_file(&path)
+                {
+                    return Some(personality);
+                }
+            }
+        }
+        None
+    }
+
+    /// Serialises the personality to its TOML representation, suitable for
+    /// round-tripping through [`Personality::from_toml`].
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("[personality]\n");
+        out.push_str(&format!("triplet = \"{}\"\n", self.triplet));
+        if let Some(sysroot) = &self.sysroot_dir {
+            out.push_str(&format!("sysroot = \"{}\"\n", sysroot.display()));
+        }
+        for (key, paths) in [
+            ("pkg_config_path", &self.pkg_config_path),
+            ("system_libdirs", &self.system_libdirs),
+            ("system_includedirs", &self.system_includedirs),
+        ] {
+            if !paths.is_empty() {
+                let items: Vec<String> = paths
+                    .iter()
+                    .map(|p| format!("\"{}\"", p.display()))
+                    .collect();
+                out.push_str(&format!("{} = [{}]\n", key, items.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+/// Splits a colon-separated INI path list.
+fn split_path_list(value: &str) -> Vec<PathBuf> {
+    value
+        .split(':')
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Unquotes a TOML basic string value.
+fn toml_string(value: &str) -> Result<String, PersonalityError> {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .map(str::to_owned)
+        .ok_or_else(|| PersonalityError::Malformed(value.to_owned()))
+}
+
+/// Parses a TOML array of basic strings into paths.
+fn toml_path_array(value: &str) -> Result<Vec<PathBuf>, PersonalityError> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .ok_or_else(|| PersonalityError::Malformed(value.to_owned()))?;
+    inner
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| toml_string(item).map(PathBuf::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INI: &str = "\
+Triplet: aarch64-linux-gnu
+SysrootDir: /sysroot
+DefaultSearchPaths: /sysroot/usr/lib/pkgconfig:/sysroot/usr/share/pkgconfig
+SystemLibraryPaths: /sysroot/usr/lib
+SystemIncludePaths: /sysroot/usr/include
+";
+
+    const TOML: &str = "\
+[personality]
+triplet = \"aarch64-linux-gnu\"
+sysroot = \"/sysroot\"
+pkg_config_path = [\"/sysroot/usr/lib/pkgconfig\", \"/sysroot/usr/share/pkgconfig\"]
+system_libdirs = [\"/sysroot/usr/lib\"]
+system_includedirs = [\"/sysroot/usr/include\"]
+";
+
+    #[test]
+    fn ini_and_toml_parse_to_the_same_personality() {
+        let from_ini = Personality::from_ini(INI).unwrap();
+        let from_toml = Personality::from_toml(TOML).unwrap();
+        assert_eq!(from_ini, from_toml);
+        assert_eq!(from_ini.triplet, "aarch64-linux-gnu");
+        assert_eq!(from_ini.sysroot_dir.as_deref(), Some(Path::new("/sysroot")));
+        assert_eq!(from_ini.pkg_config_path.len(), 2);
+    }
+
+    #[test]
+    fn toml_round_trips() {
+        let personality = Personality::from_toml(TOML).unwrap();
+        let reparsed = Personality::from_toml(&personality.to_toml()).unwrap();
+        assert_eq!(personality, reparsed);
+    }
+
+    #[test]
+    fn from_file_dispatches_on_extension() {
+        let dir = std::env::temp_dir().join("libpkgconf-personality-test");
+        fs::create_dir_all(&dir).unwrap();
+        let ini_path = dir.join("cross.personality");
+        let toml_path = dir.join("cross.toml");
+        fs::write(&ini_path, INI).unwrap();
+        fs::write(&toml_path, TOML).unwrap();
+        assert_eq!(
+            Personality::from_file(&ini_path).unwrap(),
+            Personality::from_file(&toml_path).unwrap()
+        );
+        let err = Personality::from_file(&dir.join("cross.json")).unwrap_err();
+        assert!(matches!(err, PersonalityError::UnknownFormat(_)));
+    }
+
+    #[test]
+    fn for_triple_scans_the_directory_list() {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-personality-triple-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("aarch64-linux-gnu.personality"), INI).unwrap();
+        let dirs = vec![dir.clone()];
+        let personality = Personality::for_triple_in("aarch64-linux-gnu", &dirs).unwrap();
+        assert_eq!(personality.triplet, "aarch64-linux-gnu");
+        assert!(Personality::for_triple_in("riscv64-linux-gnu", &dirs).is_none());
+    }
+
+    #[test]
+    fn host_personality_matches_the_crate_defaults() {
+        let personality = Personality::default_for_host();
+        assert!(!personality.triplet.is_empty());
+        assert!(!personality.pkg_config_path.is_empty());
+        #[cfg(unix)]
+        assert_eq!(
+            personality.pkg_config_path[0],
+            Path::new(crate::DEFAULT_PKGCONFIG_PATH[0])
+        );
+    }
+
+    #[test]
+    fn malformed_toml_value_is_an_error() {
+        let err = Personality::from_toml("[personality]\ntriplet = unquoted\n").unwrap_err();
+        assert!(matches!(err, PersonalityError::Malformed(_)));
+    }
+}
@@ -0,0 +1,692 @@
+//! Resolved packages.
+//!
+//! A [`Package`] wraps a parsed [`PcFile`] together with the identifier it
+//! is known by (the `Name:` field, or the file stem when the field is
+//! absent), which is what dependency resolution keys on.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::client::Client;
+
+use crate::dependency::DependencyList;
+use crate::fragment::FragmentList;
+use crate::parser::{Keyword, ParseError, PcFile, ResolveOptions};
+use crate::version::VersionReq;
+
+/// A resolved package backed by a `.pc` file.
+///
+/// Every flag and dependency field is expanded eagerly at construction, so
+/// the solver, cache and client can pass packages around without carrying
+/// variable maps alongside them.
+#[derive(Debug, Clone)]
+pub struct Package {
+    id: String,
+    /// The expanded `Name:` field.
+    pub name: String,
+    /// The expanded `Version:` field, or empty when absent.
+    pub version: String,
+    /// The expanded `Description:` field, or empty when absent.
+    pub description: String,
+    /// The `.pc` file the package was loaded from, or empty when parsed
+    /// from memory.
+    pub pc_path: PathBuf,
+    /// The expanded `Requires:` entries.
+    pub requires: DependencyList,
+    /// The expanded `Requires.private:` entries.
+    pub requires_private: DependencyList,
+    /// The expanded `Cflags:` fragments.
+    pub cflags: FragmentList,
+    /// The expanded `Libs:` fragments.
+    pub libs: FragmentList,
+    /// The expanded `Libs.private:` fragments.
+    pub libs_private: FragmentList,
+    /// Every present standard field, expanded, in canonical order.
+    fields: Vec<(Keyword, String)>,
+    /// Non-standard fields, expanded, in file order.
+    custom_fields: Vec<(String, String)>,
+    pc: PcFile,
+}
+
+impl Package {
+    /// Wraps a parsed `.pc` file as a package, expanding its fields
+    /// without variable overrides.
+    ///
+    /// # Panics
+    ///
+    /// Panics if field expansion fails; use [`Package::from_pc`] to handle
+    /// malformed files gracefully.
+    pub fn new(pc: PcFile) -> Package {
+        Package::from_pc(&pc, &HashMap::new()).expect("fields of a parsed .pc file expand")
+    }
+
+    /// Builds a package from a parsed `.pc` file, expanding every field
+    /// with `vars` shadowing the file-local variable definitions.
+    pub fn from_pc(pc: &PcFile, vars: &HashMap<String, String>) -> Result<Package, ParseError> {
+        let options = ResolveOptions {
+            global_vars: vars.clone(),
+            ..ResolveOptions::default()
+        };
+        let field = |keyword| {
+            pc.resolve_field_with_options(keyword, &options)
+                .map(Option::unwrap_or_default)
+        };
+        let deps = |keyword| field(keyword).map(|raw: String| DependencyList::parse(&raw));
+        let fragments = |keyword| {
+            field(keyword).and_then(|raw: String| FragmentList::parse(&raw).map_err(Into::into))
+        };
+        let name = field(Keyword::Name)?;
+        let id = if name.is_empty() {
+            pc.path
+                .as_deref()
+                .and_then(Path::file_stem)
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_owned()
+        } else {
+            name.clone()
+        };
+        let mut fields = Vec::new();
+        for &keyword in Keyword::CANONICAL_ORDER {
+            if let Some(value) = pc.resolve_field_with_options(keyword, &options)? {
+                fields.push((keyword, value));
+            }
+        }
+        let resolved_vars = pc.resolve_variables_with_options(&options)?;
+        let custom_fields = pc
+            .custom_fields()
+            .map(|(name, raw)| {
+                (
+                    name.to_owned(),
+                    crate::parser::expand_with_map(raw, &resolved_vars).into_owned(),
+                )
+            })
+            .collect();
+        Ok(Package {
+            id,
+            name,
+            version: field(Keyword::Version)?,
+            description: field(Keyword::Description)?,
+            pc_path: pc.path.clone().unwrap_or_default(),
+            requires: deps(Keyword::Requires)?,
+            requires_private: deps(Keyword::RequiresPrivate)?,
+            cflags: fragments(Keyword::Cflags)?,
+            libs: fragments(Keyword::Libs)?,
+            libs_private: fragments(Keyword::LibsPrivate)?,
+            fields,
+            custom_fields,
+            pc: pc.clone(),
+        })
+    }
+
+    /// Whether this package's version satisfies `req`.
+    pub fn version_matches(&self, req: &VersionReq) -> bool {
+        req.matches(&self.version)
+    }
+
+    /// Whether the package was loaded from an `-uninstalled.pc` file.
+    pub fn is_uninstalled(&self) -> bool {
+        self.pc_path
+            .file_name()
+            .and_then(|file| file.to_str())
+            .is_some_and(|file| file.ends_with("-uninstalled.pc"))
+    }
+
+    /// The identifier the package is resolved by.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The underlying `.pc` file.
+    pub fn pc(&self) -> &PcFile {
+        &self.pc
+    }
+
+    /// The `Provides:` entries this package satisfies, when declared.
+    pub fn provides(&self) -> Option<crate::dependency::DependencyList> {
+        self.pc.get_provides()
+    }
+
+    /// The `Conflicts:` entries this package declares, when present.
+    pub fn conflicts(&self) -> Option<crate::dependency::DependencyList> {
+        self.pc.get_conflicts()
+    }
+
+    /// Whether this package declares that it provides `name`.
+    pub fn provides_name(&self, name: &str) -> bool {
+        self.provides()
+            .is_some_and(|provides| provides.get(name).is_some())
+    }
+
+    /// The expanded `Description:` field, or empty when absent.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The expanded `URL:` field, when declared.
+    pub fn url(&self) -> Option<&str> {
+        self.field(Keyword::Url)
+    }
+
+    /// The expanded value of the non-standard field `name`, matched by its
+    /// exact (original-casing) name.
+    pub fn custom_field(&self, name: &str) -> Option<&str> {
+        self.custom_fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterates over every present standard field with its expanded value,
+    /// in canonical order.
+    pub fn all_fields(&self) -> impl Iterator<Item = (Keyword, &str)> {
+        self.fields
+            .iter()
+            .map(|(keyword, value)| (*keyword, value.as_str()))
+    }
+
+    /// An owned snapshot of the package metadata, for serialization and
+    /// display.
+    pub fn metadata(&self) -> PackageMetadata {
+        PackageMetadata {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            url: self.url().map(str::to_owned),
+            custom_fields: self.custom_fields.clone(),
+        }
+    }
+
+    /// The expanded value of `keyword`, when the field is present.
+    fn field(&self, keyword: Keyword) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == keyword)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Collects compile flags from this package and its transitive
+    /// dependency graph, in dependency order with duplicates collapsed.
+    ///
+    /// Both `Requires:` and `Requires.private:` edges are followed —
+    /// headers of private dependencies are needed at compile time either
+    /// way. The merged list is passed through the client's system-path
+    /// filtering.
+    pub fn all_cflags(&self, client: &Client) -> Result<FragmentList, ParseError> {
+        let merged = self.collect(client, true, |package| package.cflags.clone())?;
+        Ok(client.filter_cflags(merged))
+    }
+
+    /// Collects link flags from this package and its transitive dependency
+    /// graph, preserving correct `-l` link order.
+    ///
+    /// In static mode, `Requires.private:` edges are followed and each
+    /// package's `Libs.private:` fragments are folded in.
+    pub fn all_libs(&self, client: &Client, static_mode: bool) -> Result<FragmentList, ParseError> {
+        let merged = self.collect(client, static_mode, |package| {
+            if static_mode {
+                package.libs.clone().merge(package.libs_private.clone())
+            } else {
+                package.libs.clone()
+            }
+        })?;
+        Ok(client.filter_libs(merged))
+    }
+
+    /// Merges `extract(package)` across this package and its transitive
+    /// dependency graph, depth-first, honouring the client's traversal
+    /// depth limit and visiting each package at most once.
+    fn collect(
+        &self,
+        client: &Client,
+        include_private: bool,
+        extract: impl Fn(&Package) -> FragmentList,
+    ) -> Result<FragmentList, ParseError> {
+        fn walk(
+            package: &Package,
+            client: &Client,
+            include_private: bool,
+            depth: i32,
+            visited: &mut HashSet<String>,
+            merged: &mut FragmentList,
+            extract: &impl Fn(&Package) -> FragmentList,
+        ) -> Result<(), ParseError> {
+            if depth > client.max_depth() {
+                return Err(ParseError::MaxDepthExceeded {
+                    depth: client.max_depth(),
+                });
+            }
+            if !visited.insert(package.id.clone()) {
+                return Ok(());
+            }
+            *merged = std::mem::take(merged).merge(extract(package));
+            let mut deps: Vec<String> = package.requires();
+            if include_private {
+                deps.extend(package.requires_private.iter().map(|dep| dep.name.clone()));
+            }
+            for dep in deps {
+                let pc = client.load_package(&dep)?;
+                let dep_package = Package::from_pc(&pc, client.global_vars())?;
+                walk(
+                    &dep_package,
+                    client,
+                    include_private,
+                    depth + 1,
+                    visited,
+                    merged,
+                    extract,
+                )?;
+            }
+            Ok(())
+        }
+        let mut visited = HashSet::new();
+        let mut merged = FragmentList::new();
+        walk(
+            self,
+            client,
+            include_private,
+            1,
+            &mut visited,
+            &mut merged,
+            &extract,
+        )?;
+        Ok(merged)
+    }
+
+    /// The names of the packages listed in `Requires:`, without version
+    /// constraints.
+    pub fn requires(&self) -> Vec<String> {
+        self.requires.iter().map(|dep| dep.name.clone()).collect()
+    }
+}
+
+/// An owned snapshot of a package's descriptive metadata, detached from
+/// the `.pc` file it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackageMetadata {
+    /// The expanded `Name:` field.
+    pub name: String,
+    /// The expanded `Version:` field, or empty when absent.
+    pub version: String,
+    /// The expanded `Description:` field, or empty when absent.
+    pub description: String,
+    /// The expanded `URL:` field, when declared.
+    pub url: Option<String>,
+    /// Non-standard fields, expanded, in file order.
+    pub custom_fields: Vec<(String, String)>,
+}
+
+/// How one package pulls in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// A public `Requires:` edge.
+    Requires,
+    /// A `Requires.private:` edge.
+    RequiresPrivate,
+}
+
+/// The dependency graph below a set of root packages, for inspection and
+/// Graphviz rendering.
+///
+/// Unlike the flag traversals, construction does not fail on a cyclic
+/// `Requires:` chain — the cycle is recorded and the offending edge is
+/// skipped, so the graph of a broken tree can still be drawn and debugged.
+#[derive(Debug, Default)]
+pub struct PackageGraph {
+    /// `(id, version)` pairs in discovery order.
+    nodes: Vec<(String, String)>,
+    /// `(from, to, kind)` adjacency entries in discovery order.
+    edges: Vec<(String, String, EdgeKind)>,
+    cycles: Vec<Vec<String>>,
+}
+
+impl PackageGraph {
+    /// Resolves the transitive dependencies of every package in `roots`
+    /// and records the nodes and edges encountered.
+    pub fn new(roots: &[&str], client: &mut Client) -> Result<PackageGraph, ParseError> {
+        let mut graph = PackageGraph::default();
+        let mut state = TraversalState::default();
+        for root in roots {
+            graph.visit(root, client, &mut state, 1)?;
+        }
+        Ok(graph)
+    }
+
+    fn visit(
+        &mut self,
+        name: &str,
+        client: &Client,
+        state: &mut TraversalState,
+        depth: i32,
+    ) -> Result<(), ParseError> {
+        if depth > client.max_depth() {
+            return Err(ParseError::MaxDepthExceeded {
+                depth: client.max_depth(),
+            });
+        }
+        match state.enter(name) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(cycle) => {
+                if !self.cycles.contains(&cycle) {
+                    self.cycles.push(cycle);
+                }
+                return Ok(());
+            }
+        }
+        let pc = client.load_package(name)?;
+        let package = Package::from_pc(&pc, client.global_vars())?;
+        self.nodes.push((name.to_owned(), package.version.clone()));
+        let edges = package
+            .requires
+            .iter()
+            .map(|dep| (dep.name.clone(), EdgeKind::Requires))
+            .chain(
+                package
+                    .requires_private
+                    .iter()
+                    .map(|dep| (dep.name.clone(), EdgeKind::RequiresPrivate)),
+            )
+            .collect::<Vec<_>>();
+        for (dep, kind) in edges {
+            self.edges.push((name.to_owned(), dep.clone(), kind));
+            self.visit(&dep, client, state, depth + 1)?;
+        }
+        state.leave(name);
+        Ok(())
+    }
+
+    /// The discovered packages as `(id, version)` pairs, in discovery
+    /// order.
+    pub fn nodes(&self) -> &[(String, String)] {
+        &self.nodes
+    }
+
+    /// The discovered `(from, to, kind)` edges, in discovery order.
+    pub fn edges(&self) -> &[(String, String, EdgeKind)] {
+        &self.edges
+    }
+
+    /// The dependency cycles met during construction, each in reference
+    /// order.
+    pub fn cycles(&self) -> &[Vec<String>] {
+        &self.cycles
+    }
+
+    /// Renders the graph in Graphviz DOT syntax.
+    ///
+    /// Nodes are labelled `name@version`; `Requires.private:` edges are
+    /// drawn dashed to set them apart from the solid public edges.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pkgdeps {\n");
+        for (name, version) in &self.nodes {
+            dot.push_str(&format!("    {name:?} [label=\"{name}@{version}\"];\n"));
+        }
+        for (from, to, kind) in &self.edges {
+            match kind {
+                EdgeKind::Requires => dot.push_str(&format!("    {from:?} -> {to:?};\n")),
+                EdgeKind::RequiresPrivate => {
+                    dot.push_str(&format!("    {from:?} -> {to:?} [style=dashed];\n"))
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// DFS colouring shared by the dependency traversals.
+///
+/// Packages on the current path are gray — meeting one again means the
+/// `Requires:` chain loops. Finished packages are black and are skipped
+/// without error, which is what collapses diamonds to a single visit.
+#[derive(Debug, Default)]
+pub(crate) struct TraversalState {
+    path: Vec<String>,
+    visiting: HashSet<String>,
+    visited: HashSet<String>,
+}
+
+impl TraversalState {
+    /// Marks `name` as in progress.
+    ///
+    /// Returns `Ok(true)` when the node was entered, `Ok(false)` when it
+    /// has already been fully processed, and the cycle (in reference
+    /// order) when `name` is already on the current path.
+    pub(crate) fn enter(&mut self, name: &str) -> Result<bool, Vec<String>> {
+        if self.visiting.contains(name) {
+            let pos = self.path.iter().position(|p| p == name).unwrap_or(0);
+            return Err(self.path[pos..].to_vec());
+        }
+        if self.visited.contains(name) {
+            return Ok(false);
+        }
+        self.visiting.insert(name.to_owned());
+        self.path.push(name.to_owned());
+        Ok(true)
+    }
+
+    /// Marks `name` as fully processed, removing it from the current path.
+    pub(crate) fn leave(&mut self, name: &str) {
+        self.visiting.remove(name);
+        self.path.pop();
+        self.visited.insert(name.to_owned());
+    }
+}
+
+/// Extracts the package names from a `Requires:`-style field, skipping
+/// version comparators and the versions that follow them.
+pub(crate) fn dependency_names(field: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut tokens = field
+        .split([',', ' ', '\t'])
+        .filter(|token| !token.is_empty());
+    while let Some(token) = tokens.next() {
+        if token.starts_with(['<', '>', '=', '!']) {
+            // A comparator; the next token is the version it applies to.
+            tokens.next();
+        } else {
+            names.push(token.to_owned());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pc(content: &str) -> PcFile {
+        PcFile::parse_str(content).unwrap()
+    }
+
+    #[test]
+    fn package_id_comes_from_name_field() {
+        let package = Package::new(pc("Name: foo\nVersion: 1.0\nDescription: d\n"));
+        assert_eq!(package.id(), "foo");
+    }
+
+    #[test]
+    fn from_pc_expands_fields_with_overrides() {
+        let file = pc(
+            "prefix=/usr\nName: foo\nVersion: 1.2\nDescription: d\n\
+             Requires: bar >= 1.0\nCflags: -I${prefix}/include\nLibs: -L${prefix}/lib -lfoo\n",
+        );
+        let vars = HashMap::from([("prefix".to_owned(), "/opt".to_owned())]);
+        let package = Package::from_pc(&file, &vars).unwrap();
+        assert_eq!(package.version, "1.2");
+        assert_eq!(package.cflags.render(' '), "-I/opt/include");
+        assert_eq!(package.libs.render(' '), "-L/opt/lib -lfoo");
+        assert_eq!(package.requires.get("bar").unwrap().version.as_deref(), Some("1.0"));
+    }
+
+    #[test]
+    fn metadata_accessors_expose_expanded_fields() {
+        let vars = HashMap::new();
+        let package = Package::from_pc(
+            &pc("prefix=/usr\nName: foo\nVersion: 1.2\nDescription: The ${prefix} foo\n\
+                 URL: https://example.org/foo\nX-Custom: ${prefix}/share/foo\n\
+                 Cflags: -I${prefix}/include\n"),
+            &vars,
+        )
+        .unwrap();
+        assert_eq!(package.description(), "The /usr foo");
+        assert_eq!(package.url(), Some("https://example.org/foo"));
+        assert_eq!(package.custom_field("X-Custom"), Some("/usr/share/foo"));
+        assert_eq!(package.custom_field("missing"), None);
+        let fields: Vec<(Keyword, &str)> = package.all_fields().collect();
+        assert_eq!(
+            fields,
+            [
+                (Keyword::Name, "foo"),
+                (Keyword::Description, "The /usr foo"),
+                (Keyword::Version, "1.2"),
+                (Keyword::Url, "https://example.org/foo"),
+                (Keyword::Cflags, "-I/usr/include"),
+            ]
+        );
+        let metadata = package.metadata();
+        assert_eq!(metadata.name, "foo");
+        assert_eq!(metadata.version, "1.2");
+        assert_eq!(metadata.url.as_deref(), Some("https://example.org/foo"));
+        assert_eq!(
+            metadata.custom_fields,
+            [("X-Custom".to_owned(), "/usr/share/foo".to_owned())]
+        );
+    }
+
+    #[test]
+    fn version_matches_delegates_to_the_requirement() {
+        let package = Package::new(pc("Name: foo\nVersion: 1.4\nDescription: d\n"));
+        assert!(package.version_matches(&crate::version::VersionReq::parse(">= 1.2").unwrap()));
+        assert!(!package.version_matches(&crate::version::VersionReq::parse("< 1.0").unwrap()));
+    }
+
+    #[test]
+    fn uninstalled_is_detected_from_the_path() {
+        let mut file = pc("Name: foo\nVersion: 1.0\nDescription: d\n");
+        file.path = Some(PathBuf::from("/build/foo-uninstalled.pc"));
+        assert!(Package::new(file.clone()).is_uninstalled());
+        file.path = Some(PathBuf::from("/usr/lib/pkgconfig/foo.pc"));
+        assert!(!Package::new(file).is_uninstalled());
+    }
+
+    fn tree_client(label: &str) -> Client {
+        let dir = std::env::temp_dir().join(format!("libpkgconf-pkg-{label}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("top.pc"),
+            "Name: top\nVersion: 1.0\nDescription: d\nRequires: mid\n\
+             Cflags: -I/opt/top/include\nLibs: -L/opt/top/lib -ltop\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("mid.pc"),
+            "Name: mid\nVersion: 1.0\nDescription: d\nRequires: leaf\n\
+             Cflags: -I/opt/mid/include\nLibs: -lmid\nLibs.private: -lmid-static\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("leaf.pc"),
+            "Name: leaf\nVersion: 1.0\nDescription: d\n\
+             Cflags: -I/opt/leaf/include\nLibs: -lleaf\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        client
+    }
+
+    #[test]
+    fn all_cflags_walks_the_dependency_tree() {
+        let client = tree_client("cflags");
+        let package = client.resolve_package("top", None).unwrap();
+        assert_eq!(
+            package.all_cflags(&client).unwrap().render(' '),
+            "-I/opt/top/include -I/opt/mid/include -I/opt/leaf/include"
+        );
+    }
+
+    #[test]
+    fn all_libs_preserves_link_order_and_static_mode() {
+        let client = tree_client("libs");
+        let package = client.resolve_package("top", None).unwrap();
+        assert_eq!(
+            package.all_libs(&client, false).unwrap().render(' '),
+            "-L/opt/top/lib -ltop -lmid -lleaf"
+        );
+        assert_eq!(
+            package.all_libs(&client, true).unwrap().render(' '),
+            "-L/opt/top/lib -ltop -lmid -lmid-static -lleaf"
+        );
+    }
+
+    fn graph_dir(label: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-graph-{label}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.join(format!("{name}.pc")), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn package_graph_renders_labelled_dot_with_edge_styles() {
+        let dir = graph_dir(
+            "dot",
+            &[
+                (
+                    "top",
+                    "Name: top\nVersion: 1.0\nDescription: d\nRequires: mid\n\
+                     Requires.private: hidden\n",
+                ),
+                ("mid", "Name: mid\nVersion: 2.1\nDescription: d\n"),
+                ("hidden", "Name: hidden\nVersion: 0.9\nDescription: d\n"),
+            ],
+        );
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        let graph = PackageGraph::new(&["top"], &mut client).unwrap();
+        assert!(graph.cycles().is_empty());
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph pkgdeps {\n"), "{dot}");
+        assert!(dot.contains("\"top\" [label=\"top@1.0\"];"), "{dot}");
+        assert!(dot.contains("\"mid\" [label=\"mid@2.1\"];"), "{dot}");
+        assert!(dot.contains("\"top\" -> \"mid\";"), "{dot}");
+        assert!(
+            dot.contains("\"top\" -> \"hidden\" [style=dashed];"),
+            "{dot}"
+        );
+        assert!(dot.ends_with("}\n"), "{dot}");
+    }
+
+    #[test]
+    fn package_graph_records_cycles_instead_of_failing() {
+        let dir = graph_dir(
+            "cycle",
+            &[
+                ("a", "Name: a\nVersion: 1.0\nDescription: d\nRequires: b\n"),
+                ("b", "Name: b\nVersion: 1.0\nDescription: d\nRequires: a\n"),
+            ],
+        );
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        let graph = PackageGraph::new(&["a"], &mut client).unwrap();
+        assert_eq!(graph.cycles(), [vec!["a".to_owned(), "b".to_owned()]]);
+        assert_eq!(graph.nodes().len(), 2);
+    }
+
+    #[test]
+    fn requires_names_skip_version_constraints() {
+        let package = Package::new(pc(
+            "Name: foo\nVersion: 1.0\nDescription: d\nRequires: bar >= 1.2, baz, qux = 2.0\n",
+        ));
+        assert_eq!(package.requires(), vec!["bar", "baz", "qux"]);
+    }
+}
@@ -0,0 +1,551 @@
+//! Dependency queue solving.
+//!
+//! A [`PackageQueue`] collects the packages that form the resolution world,
+//! then [`PackageQueue::solve`] flattens them into link order: each package
+//! precedes the packages it requires, duplicates collapsed to their first
+//! occurrence. The queue is stateful — after a successful solve the order
+//! can be re-queried via [`PackageQueue::ordered_packages`] without
+//! re-solving.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::client::Client;
+use crate::parser::ParseError;
+use crate::pkg::{Package, TraversalState};
+use crate::version::VersionReq;
+
+/// An error produced while solving a [`PackageQueue`].
+#[derive(Debug)]
+pub enum SolveError {
+    /// A package requires a dependency that is not in the queue.
+    MissingDependency {
+        /// The package whose requirement could not be satisfied.
+        package: String,
+        /// The name of the missing dependency.
+        dependency: String,
+    },
+    /// A package's `Requires:` chain loops back on itself.
+    CircularDependency {
+        /// The packages participating in the cycle, in reference order.
+        cycle: Vec<String>,
+    },
+    /// Two packages in the solution are declared incompatible.
+    ConflictDetected {
+        /// The package whose `Conflicts:` field matched.
+        package: String,
+        /// The conflicting package, as named by the matching entry.
+        conflicts_with: String,
+    },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::MissingDependency {
+                package,
+                dependency,
+            } => write!(f, "package '{package}' requires unknown package '{dependency}'"),
+            SolveError::CircularDependency { cycle } => {
+                write!(f, "circular dependency detected: {}", cycle.join(" -> "))
+            }
+            SolveError::ConflictDetected {
+                package,
+                conflicts_with,
+            } => write!(f, "package '{package}' conflicts with '{conflicts_with}'"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// A queue of packages to be flattened into dependency order.
+#[derive(Debug, Default)]
+pub struct PackageQueue {
+    packages: Vec<Package>,
+    solved: Vec<Package>,
+    is_solved: bool,
+}
+
+impl PackageQueue {
+    /// Creates an empty queue.
+    pub fn new() -> PackageQueue {
+        PackageQueue::default()
+    }
+
+    /// Adds a package to the resolution world. Invalidates any previous
+    /// solve result.
+    pub fn push(&mut self, package: Package) {
+        self.packages.push(package);
+        self.solved.clear();
+        self.is_solved = false;
+    }
+
+    /// Flattens the queue into link order and returns the solved packages.
+    ///
+    /// The solved order is also stored in the queue and can be re-queried
+    /// via [`PackageQueue::ordered_packages`] without copying.
+    pub fn solve(&mut self) -> Result<Vec<Package>, SolveError> {
+        self.is_solved = false;
+        self.solved.clear();
+        let mut state = TraversalState::default();
+        let mut emitted = HashSet::new();
+        let roots: Vec<String> = self.packages.iter().map(|p| p.id().to_owned()).collect();
+        let mut ordered = Vec::new();
+        for root in &roots {
+            self.visit(root, root, &mut state, &mut emitted, &mut ordered)?;
+        }
+        self.check_conflicts(&ordered)?;
+        self.solved = ordered.clone();
+        self.is_solved = true;
+        Ok(ordered)
+    }
+
+    /// Emits `name` and then its requirements, depth-first.
+    fn visit(
+        &self,
+        dependent: &str,
+        name: &str,
+        state: &mut TraversalState,
+        emitted: &mut HashSet<String>,
+        ordered: &mut Vec<Package>,
+    ) -> Result<(), SolveError> {
+        // A name already on the current path means the Requires: chain
+        // loops; one that merely finished has already been emitted.
+        match state.enter(name) {
+            Err(cycle) => return Err(SolveError::CircularDependency { cycle }),
+            Ok(false) => return Ok(()),
+            Ok(true) => {}
+        }
+        if emitted.contains(name) {
+            state.leave(name);
+            return Ok(());
+        }
+        // An exact id match wins; otherwise fall back to a package that
+        // declares the name in its Provides: field.
+        let Some(package) = self
+            .packages
+            .iter()
+            .find(|p| p.id() == name)
+            .or_else(|| self.packages.iter().find(|p| p.provides_name(name)))
+        else {
+            return Err(SolveError::MissingDependency {
+                package: dependent.to_owned(),
+                dependency: name.to_owned(),
+            });
+        };
+        emitted.insert(name.to_owned());
+        // A provides-match resolves under a different name than its id;
+        // record the id too so the package is only emitted once.
+        if package.id() != name && !emitted.insert(package.id().to_owned()) {
+            state.leave(name);
+            return Ok(());
+        }
+        ordered.push(package.clone());
+        for dep in package.requires() {
+            self.visit(name, &dep, state, emitted, ordered)?;
+        }
+        state.leave(name);
+        Ok(())
+    }
+
+    /// Fails if any solved package's `Conflicts:` entries match another
+    /// package in the solution. Setting `PKG_CONFIG_IGNORE_CONFLICTS` in
+    /// the environment skips the check entirely.
+    fn check_conflicts(&self, ordered: &[Package]) -> Result<(), SolveError> {
+        if std::env::var_os("PKG_CONFIG_IGNORE_CONFLICTS").is_some() {
+            return Ok(());
+        }
+        for package in ordered {
+            let Some(conflicts) = package.conflicts() else {
+                continue;
+            };
+            for entry in &conflicts {
+                let clash = ordered.iter().any(|other| {
+                    other.id() == entry.name
+                        && other
+                            .pc()
+                            .version()
+                            .is_none_or(|version| entry.is_satisfied_by(version))
+                });
+                if clash {
+                    return Err(SolveError::ConflictDetected {
+                        package: package.id().to_owned(),
+                        conflicts_with: entry.name.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The packages in solved order, or an empty slice if the queue has not
+    /// been (successfully) solved.
+    pub fn ordered_packages(&self) -> &[Package] {
+        &self.solved
+    }
+
+    /// Whether the last [`PackageQueue::solve`] call succeeded.
+    pub fn is_solved(&self) -> bool {
+        self.is_solved
+    }
+
+    /// Resets the queue for reuse, dropping packages and any solve result.
+    pub fn clear(&mut self) {
+        self.packages.clear();
+        self.solved.clear();
+        self.is_solved = false;
+    }
+}
+
+/// A queue of top-level package requests resolved against a [`Client`].
+///
+/// Unlike [`PackageQueue`], which flattens an in-memory world into link
+/// order, a `DependencyQueue` loads packages through a client and returns
+/// them in topological order — dependencies before dependents — ready for
+/// constructing a link command line.
+#[derive(Debug, Default)]
+pub struct DependencyQueue {
+    requests: Vec<(String, Option<VersionReq>)>,
+}
+
+impl DependencyQueue {
+    /// Creates an empty queue.
+    pub fn new() -> DependencyQueue {
+        DependencyQueue::default()
+    }
+
+    /// Adds a top-level request, optionally constrained to a version.
+    pub fn push(&mut self, name: &str, version_req: Option<VersionReq>) {
+        self.requests.push((name.to_owned(), version_req));
+    }
+
+    /// Resolves every request through `client` and returns the packages
+    /// in topological order, each appearing once.
+    ///
+    /// Repeated requests for the same package are deduplicated, but every
+    /// version constraint is still validated against the resolved package.
+    pub fn solve(&self, client: &Client) -> Result<Vec<Package>, ParseError> {
+        self.solve_with(client, false)
+    }
+
+    /// Like [`DependencyQueue::solve`], but follows `Requires.private:`
+    /// edges as well, for static link lines.
+    pub fn solve_static(&self, client: &Client) -> Result<Vec<Package>, ParseError> {
+        self.solve_with(client, true)
+    }
+
+    fn solve_with(&self, client: &Client, include_private: bool) -> Result<Vec<Package>, ParseError> {
+        let mut state = TraversalState::default();
+        let mut ordered = Vec::new();
+        for (name, version_req) in &self.requests {
+            // resolve_package validates the constraint even when the
+            // package was already emitted for an earlier request.
+            let package = client.resolve_package(
+                name,
+                version_req.as_ref().map(ToString::to_string).as_deref(),
+            )?;
+            self.emit(client, package, include_private, 1, &mut state, &mut ordered)?;
+        }
+        Ok(ordered)
+    }
+
+    /// Emits `package`'s dependencies and then the package itself.
+    fn emit(
+        &self,
+        client: &Client,
+        package: Package,
+        include_private: bool,
+        depth: i32,
+        state: &mut TraversalState,
+        ordered: &mut Vec<Package>,
+    ) -> Result<(), ParseError> {
+        if depth > client.max_depth() {
+            return Err(ParseError::MaxDepthExceeded {
+                depth: client.max_depth(),
+            });
+        }
+        match state.enter(package.id()) {
+            Err(cycle) => return Err(ParseError::CircularDependency { cycle }),
+            Ok(false) => return Ok(()),
+            Ok(true) => {}
+        }
+        let mut deps = package.requires();
+        if include_private {
+            deps.extend(package.requires_private.iter().map(|dep| dep.name.clone()));
+        }
+        for dep in deps {
+            let resolved = client.resolve_package(&dep, None)?;
+            self.emit(client, resolved, include_private, depth + 1, state, ordered)?;
+        }
+        state.leave(package.id());
+        ordered.push(package);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // The environment is process-global, so these tests share one lock
+    // with every other module that touches `PKG_CONFIG_*` variables.
+    use crate::ENV_LOCK;
+    use crate::parser::PcFile;
+
+    fn package(name: &str, requires: &str) -> Package {
+        let content = format!(
+            "Name: {name}\nVersion: 1.0\nDescription: d\nRequires: {requires}\n"
+        );
+        Package::new(PcFile::parse_str(&content).unwrap())
+    }
+
+    #[test]
+    fn solve_orders_dependents_before_dependencies() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("app", "mid"));
+        queue.push(package("mid", "base"));
+        queue.push(package("base", ""));
+        let ordered = queue.solve().unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["app", "mid", "base"]);
+    }
+
+    #[test]
+    fn solved_order_is_queryable_without_resolving_again() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("base", ""));
+        assert!(!queue.is_solved());
+        assert!(queue.ordered_packages().is_empty());
+        queue.solve().unwrap();
+        assert!(queue.is_solved());
+        assert_eq!(queue.ordered_packages().len(), 1);
+    }
+
+    #[test]
+    fn pushing_invalidates_previous_solve() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("base", ""));
+        queue.solve().unwrap();
+        queue.push(package("extra", ""));
+        assert!(!queue.is_solved());
+    }
+
+    #[test]
+    fn missing_dependency_is_an_error() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("app", "ghost"));
+        let err = queue.solve().unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::MissingDependency { package, dependency }
+                if package == "app" && dependency == "ghost"
+        ));
+        assert!(!queue.is_solved());
+    }
+
+    #[test]
+    fn clear_resets_the_queue() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("base", ""));
+        queue.solve().unwrap();
+        queue.clear();
+        assert!(!queue.is_solved());
+        assert!(queue.ordered_packages().is_empty());
+    }
+
+    #[test]
+    fn provides_satisfies_a_missing_dependency() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("app", "virtual-ssl"));
+        queue.push(Package::new(
+            PcFile::parse_str(
+                "Name: openssl
+Version: 3.0
+Description: d
+Provides: virtual-ssl = 3.0
+",
+            )
+            .unwrap(),
+        ));
+        let ordered = queue.solve().unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["app", "openssl"]);
+    }
+
+    #[test]
+    fn exact_name_match_is_preferred_over_provides() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("app", "ssl"));
+        queue.push(Package::new(
+            PcFile::parse_str("Name: imposter
+Version: 1.0
+Description: d
+Provides: ssl
+")
+                .unwrap(),
+        ));
+        queue.push(package("ssl", ""));
+        let ordered = queue.solve().unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["app", "ssl", "imposter"]);
+    }
+
+    fn conflicting_pair() -> PackageQueue {
+        let mut queue = PackageQueue::new();
+        queue.push(Package::new(
+            PcFile::parse_str(
+                "Name: app
+Version: 1.0
+Description: d
+Requires: legacy
+Conflicts: legacy < 2.0
+",
+            )
+            .unwrap(),
+        ));
+        queue.push(package("legacy", ""));
+        queue
+    }
+
+    #[test]
+    fn conflicting_versions_fail_the_solve() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let err = conflicting_pair().solve().unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::ConflictDetected { package, conflicts_with }
+                if package == "app" && conflicts_with == "legacy"
+        ));
+    }
+
+    #[test]
+    fn conflict_constraint_must_match_the_version() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        let mut queue = PackageQueue::new();
+        queue.push(Package::new(
+            PcFile::parse_str(
+                "Name: app
+Version: 1.0
+Description: d
+Requires: legacy
+Conflicts: legacy < 1.0
+",
+            )
+            .unwrap(),
+        ));
+        queue.push(package("legacy", ""));
+        // legacy is 1.0, which does not match `< 1.0`.
+        assert!(queue.solve().is_ok());
+    }
+
+    #[test]
+    fn ignore_conflicts_env_var_bypasses_the_check() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        // SAFETY: the crate-wide ENV_LOCK serialises environment access
+        // across every test module that touches these variables.
+        unsafe { std::env::set_var("PKG_CONFIG_IGNORE_CONFLICTS", "1") };
+        let result = conflicting_pair().solve();
+        unsafe { std::env::remove_var("PKG_CONFIG_IGNORE_CONFLICTS") };
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn two_package_cycle_is_detected() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("a", "b"));
+        queue.push(package("b", "a"));
+        let err = queue.solve().unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::CircularDependency { cycle } if cycle == ["a", "b"]
+        ));
+    }
+
+    #[test]
+    fn three_package_cycle_lists_members_in_order() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("a", "b"));
+        queue.push(package("b", "c"));
+        queue.push(package("c", "a"));
+        let err = queue.solve().unwrap_err();
+        assert!(matches!(
+            err,
+            SolveError::CircularDependency { cycle } if cycle == ["a", "b", "c"]
+        ));
+    }
+
+    fn diamond_client(label: &str) -> Client {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-queue-{label}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let files = [
+            ("app", "Requires: left, right\n"),
+            ("left", "Requires: base\nRequires.private: hidden\n"),
+            ("right", "Requires: base\n"),
+            ("base", ""),
+            ("hidden", ""),
+        ];
+        for (name, extra) in files {
+            std::fs::write(
+                dir.join(format!("{name}.pc")),
+                format!("Name: {name}\nVersion: 1.0\nDescription: d\n{extra}"),
+            )
+            .unwrap();
+        }
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        client
+    }
+
+    #[test]
+    fn dependency_queue_orders_dependencies_first() {
+        let client = diamond_client("diamond");
+        let mut queue = DependencyQueue::new();
+        queue.push("app", None);
+        let ordered = queue.solve(&client).unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["base", "left", "right", "app"]);
+    }
+
+    #[test]
+    fn dependency_queue_static_mode_follows_private_edges() {
+        let client = diamond_client("static");
+        let mut queue = DependencyQueue::new();
+        queue.push("app", None);
+        let ordered = queue.solve_static(&client).unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["base", "hidden", "left", "right", "app"]);
+    }
+
+    #[test]
+    fn dependency_queue_validates_repeated_version_constraints() {
+        let client = diamond_client("versions");
+        let mut queue = DependencyQueue::new();
+        queue.push("base", Some(VersionReq::parse(">= 1.0").unwrap()));
+        queue.push("base", Some(VersionReq::parse(">= 2.0").unwrap()));
+        let err = queue.solve(&client).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::VersionMismatch { name, .. } if name == "base"
+        ));
+        // The duplicate itself is fine when both constraints hold.
+        let mut queue = DependencyQueue::new();
+        queue.push("base", Some(VersionReq::parse(">= 1.0").unwrap()));
+        queue.push("base", Some(VersionReq::parse("<= 1.0").unwrap()));
+        assert_eq!(queue.solve(&client).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn shared_dependencies_are_emitted_once() {
+        let mut queue = PackageQueue::new();
+        queue.push(package("a", "common"));
+        queue.push(package("b", "common"));
+        queue.push(package("common", ""));
+        let ordered = queue.solve().unwrap();
+        let ids: Vec<&str> = ordered.iter().map(Package::id).collect();
+        assert_eq!(ids, vec!["a", "common", "b"]);
+    }
+}
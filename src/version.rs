@@ -0,0 +1,622 @@
+//! Version comparison following the RPM `rpmvercmp` algorithm, which is
+//! what pkg-config uses to evaluate version constraints.
+//!
+//! Versions are compared segment-by-segment, where a segment is a maximal
+//! run of digits or of alphabetic characters. Numeric segments compare
+//! numerically (ignoring leading zeros), alphabetic segments compare
+//! lexically, and numeric segments always sort newer than alphabetic ones.
+//! A `~` sorts before everything, including the end of the string, so
+//! `1.0~rc1` is older than `1.0`. An RPM-style epoch prefix (`2:1.0`) is
+//! the most significant component; a missing epoch reads as epoch 0.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// A version constraint operator, as written in `Requires:` fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparator {
+    /// Parses a comparator token; `=` and `==` are equivalent.
+    pub fn parse(s: &str) -> Option<Comparator> {
+        match s {
+            "=" | "==" => Some(Comparator::Equal),
+            "!=" => Some(Comparator::NotEqual),
+            "<" => Some(Comparator::Less),
+            "<=" => Some(Comparator::LessOrEqual),
+            ">" => Some(Comparator::Greater),
+            ">=" => Some(Comparator::GreaterOrEqual),
+            _ => None,
+        }
+    }
+
+    /// The canonical spelling of the operator.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparator::Equal => "=",
+            Comparator::NotEqual => "!=",
+            Comparator::Less => "<",
+            Comparator::LessOrEqual => "<=",
+            Comparator::Greater => ">",
+            Comparator::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error produced while parsing a [`Version`] or [`VersionReq`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionError {
+    /// The input was empty.
+    Empty,
+    /// The input was not a `op version` requirement.
+    MalformedRequirement {
+        /// The offending input.
+        input: String,
+    },
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionError::Empty => f.write_str("empty version string"),
+            VersionError::MalformedRequirement { input } => {
+                write!(f, "malformed version requirement: {input:?}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for VersionError {}
+
+/// A parsed version, ordered by the rpmvercmp rules.
+///
+/// The comparison key is computed once at parse time, so `Version` values
+/// are cheap to compare repeatedly and can live in sorted collections like
+/// `BTreeMap`. Equality follows the comparison rules — `1.00` equals `1.0`
+/// — while [`fmt::Display`] reproduces the original spelling.
+#[derive(Debug, Clone)]
+pub struct Version {
+    raw: String,
+    key: Vec<VersionComponent>,
+}
+
+impl Version {
+    /// Parses a version string, pre-computing its comparison key.
+    pub fn parse(s: &str) -> Result<Version, VersionError> {
+        if s.is_empty() {
+            return Err(VersionError::Empty);
+        }
+        Ok(Version {
+            raw: s.to_owned(),
+            key: sort_key(s),
+        })
+    }
+
+    /// The original spelling of the version.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// The pre-computed comparison components.
+    pub fn components(&self) -> &[VersionComponent] {
+        &self.key
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Version {}
+
+impl core::hash::Hash for Version {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        compare_keys(&self.key, &other.key)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A single version requirement: a comparator applied to a version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    /// The constraint operator.
+    pub comparator: Comparator,
+    /// The version the operator compares against.
+    pub version: String,
+}
+
+impl VersionReq {
+    /// Parses an `op version` requirement such as `">= 1.0"`; whitespace
+    /// around either part is ignored.
+    pub fn parse(s: &str) -> Result<VersionReq, VersionError> {
+        let mut parts = s.split_whitespace();
+        let (Some(op), Some(version), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(VersionError::MalformedRequirement { input: s.to_owned() });
+        };
+        let Some(comparator) = Comparator::parse(op) else {
+            return Err(VersionError::MalformedRequirement { input: s.to_owned() });
+        };
+        Ok(VersionReq {
+            comparator,
+            version: version.to_owned(),
+        })
+    }
+
+    /// Whether `candidate` satisfies this requirement.
+    pub fn matches(&self, candidate: &str) -> bool {
+        let ordering = compare(candidate, &self.version);
+        match self.comparator {
+            Comparator::Equal => ordering == Ordering::Equal,
+            Comparator::NotEqual => ordering != Ordering::Equal,
+            Comparator::Less => ordering == Ordering::Less,
+            Comparator::LessOrEqual => ordering != Ordering::Greater,
+            Comparator::Greater => ordering == Ordering::Greater,
+            Comparator::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.comparator, self.version)
+    }
+}
+
+/// Compares two version strings, returning how `a` relates to `b`.
+///
+/// A convenience wrapper over [`compare_raw`], the allocation-free
+/// implementation the dependency solver calls on its hot path.
+pub fn compare(a: &str, b: &str) -> Ordering {
+    compare_raw(a, b)
+}
+
+/// Splits an RPM-style `epoch:` prefix off a version string.
+///
+/// Returns the epoch digits (`"0"` when absent) and the remainder. A `:`
+/// not preceded exclusively by digits is not an epoch separator.
+fn split_epoch(s: &str) -> (&str, &str) {
+    if let Some((epoch, rest)) = s.split_once(':')
+        && !epoch.is_empty()
+        && epoch.bytes().all(|c| c.is_ascii_digit())
+    {
+        (epoch, rest)
+    } else {
+        ("0", s)
+    }
+}
+
+/// Compares two version strings without allocating, walking the byte
+/// slices segment by segment instead of building [`VersionComponent`]
+/// lists; [`compare_str`] remains as the original name.
+pub fn compare_raw(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let (epoch_a, a) = split_epoch(a);
+    let (epoch_b, b) = split_epoch(b);
+    let epoch_ord = compare_numeric(epoch_a.as_bytes(), epoch_b.as_bytes());
+    if epoch_ord != Ordering::Equal {
+        return epoch_ord;
+    }
+    let mut a = a.as_bytes();
+    let mut b = b.as_bytes();
+    loop {
+        // A tilde sorts before everything, including end-of-string.
+        match (a.first(), b.first()) {
+            (Some(b'~'), Some(b'~')) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            }
+            (Some(b'~'), _) => return Ordering::Less,
+            (_, Some(b'~')) => return Ordering::Greater,
+            _ => {}
+        }
+        // Skip any other non-alphanumeric separators.
+        while a.first().is_some_and(|c| !c.is_ascii_alphanumeric()) {
+            a = &a[1..];
+        }
+        while b.first().is_some_and(|c| !c.is_ascii_alphanumeric()) {
+            b = &b[1..];
+        }
+        if a.is_empty() || b.is_empty() {
+            return a.len().cmp(&b.len());
+        }
+        let numeric = a[0].is_ascii_digit();
+        let (seg_a, rest_a) = take_segment(a, numeric);
+        let (seg_b, rest_b) = take_segment(b, numeric);
+        if seg_b.is_empty() {
+            // Differing segment types: numeric sorts newer than alphabetic.
+            return if numeric { Ordering::Greater } else { Ordering::Less };
+        }
+        let ord = if numeric {
+            compare_numeric(seg_a, seg_b)
+        } else {
+            seg_a.cmp(seg_b)
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+        a = rest_a;
+        b = rest_b;
+    }
+}
+
+/// Compares two version strings without allocating.
+///
+/// The original name of [`compare_raw`], kept for callers that predate it.
+pub fn compare_str(a: &str, b: &str) -> Ordering {
+    compare_raw(a, b)
+}
+
+/// One pre-parsed segment of a version string, as produced by [`sort_key`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VersionComponent {
+    /// A `~` marker; sorts before everything, including end-of-key.
+    Tilde,
+    /// A run of alphabetic characters.
+    Alpha(String),
+    /// A run of digits, leading zeros stripped.
+    Numeric(String),
+}
+
+/// Pre-computes the comparison key of a version string.
+///
+/// Sorting many versions with [`compare`] re-parses each string on every
+/// comparison, costing O(N²) parses; computing keys once up front and
+/// comparing with [`compare_keys`] keeps a sort at O(N log N) comparisons.
+pub fn sort_key(s: &str) -> Vec<VersionComponent> {
+    let mut key = Vec::new();
+    // The epoch is the most significant component; emit it first so that
+    // any epoch beats any epoch-less version (epoch 0 strips to "").
+    let (epoch, s) = split_epoch(s);
+    key.push(VersionComponent::Numeric(
+        String::from_utf8_lossy(strip_leading_zeros(epoch.as_bytes())).into_owned(),
+    ));
+    let mut rest = s.as_bytes();
+    while let Some(&c) = rest.first() {
+        if c == b'~' {
+            key.push(VersionComponent::Tilde);
+            rest = &rest[1..];
+        } else if c.is_ascii_digit() {
+            let (seg, tail) = take_segment(rest, true);
+            let seg = strip_leading_zeros(seg);
+            key.push(VersionComponent::Numeric(
+                String::from_utf8_lossy(seg).into_owned(),
+            ));
+            rest = tail;
+        } else if c.is_ascii_alphabetic() {
+            let (seg, tail) = take_segment(rest, false);
+            key.push(VersionComponent::Alpha(
+                String::from_utf8_lossy(seg).into_owned(),
+            ));
+            rest = tail;
+        } else {
+            // Separators carry no ordering information.
+            rest = &rest[1..];
+        }
+    }
+    key
+}
+
+/// Compares two pre-computed sort keys; equivalent to running
+/// [`compare_str`] on the original strings.
+pub fn compare_keys(a: &[VersionComponent], b: &[VersionComponent]) -> Ordering {
+    let mut a = a.iter();
+    let mut b = b.iter();
+    loop {
+        let ord = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            // Tilde sorts before everything, including end-of-key.
+            (Some(VersionComponent::Tilde), Some(VersionComponent::Tilde)) => Ordering::Equal,
+            (Some(VersionComponent::Tilde), _) => return Ordering::Less,
+            (_, Some(VersionComponent::Tilde)) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(VersionComponent::Numeric(x)), Some(VersionComponent::Numeric(y))) => {
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(VersionComponent::Alpha(x)), Some(VersionComponent::Alpha(y))) => x.cmp(y),
+            // Differing segment types: numeric sorts newer than alphabetic.
+            (Some(VersionComponent::Numeric(_)), Some(VersionComponent::Alpha(_))) => {
+                return Ordering::Greater;
+            }
+            (Some(VersionComponent::Alpha(_)), Some(VersionComponent::Numeric(_))) => {
+                return Ordering::Less;
+            }
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+/// Sorts version strings oldest-first in place, pre-computing each sort
+/// key once.
+pub fn sort_versions(versions: &mut Vec<&str>) {
+    let mut keyed: Vec<(Vec<VersionComponent>, &str)> =
+        versions.iter().map(|v| (sort_key(v), *v)).collect();
+    keyed.sort_by(|(ka, _), (kb, _)| compare_keys(ka, kb));
+    versions.clear();
+    versions.extend(keyed.into_iter().map(|(_, v)| v));
+}
+
+/// The owned-string counterpart of [`sort_versions`].
+pub fn sort_versions_owned(versions: &mut Vec<String>) {
+    let mut keyed: Vec<(Vec<VersionComponent>, String)> = versions
+        .drain(..)
+        .map(|v| (sort_key(&v), v))
+        .collect();
+    keyed.sort_by(|(ka, _), (kb, _)| compare_keys(ka, kb));
+    versions.extend(keyed.into_iter().map(|(_, v)| v));
+}
+
+/// Whether `version` satisfies every constraint in a comma-separated
+/// range such as `">= 1.2, < 2.0"`.
+///
+/// An empty range is trivially satisfied; a constraint that fails to parse
+/// makes the whole range unsatisfiable.
+pub fn satisfies_range(version: &str, range: &str) -> bool {
+    range
+        .split(',')
+        .map(str::trim)
+        .filter(|constraint| !constraint.is_empty())
+        .all(|constraint| {
+            VersionReq::parse(constraint).is_ok_and(|req| req.matches(version))
+        })
+}
+
+/// The newest version in `versions`, or `None` when the slice is empty.
+pub fn latest_version<'a>(versions: &[&'a str]) -> Option<&'a str> {
+    versions.iter().copied().max_by(|a, b| compare(a, b))
+}
+
+/// Splits off the leading digit or alphabetic segment.
+fn take_segment(s: &[u8], numeric: bool) -> (&[u8], &[u8]) {
+    let len = s
+        .iter()
+        .take_while(|c| {
+            if numeric {
+                c.is_ascii_digit()
+            } else {
+                c.is_ascii_alphabetic()
+            }
+        })
+        .count();
+    s.split_at(len)
+}
+
+/// Compares two digit segments numerically without overflowing on long
+/// version numbers: strip leading zeros, then longer wins, then lexical.
+fn compare_numeric(a: &[u8], b: &[u8]) -> Ordering {
+    let a = strip_leading_zeros(a);
+    let b = strip_leading_zeros(b);
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn strip_leading_zeros(s: &[u8]) -> &[u8] {
+    let zeros = s.iter().take_while(|&&c| c == b'0').count();
+    &s[zeros..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn equal_versions() {
+        assert_eq!(compare("1.0.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare("1.00", "1.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn numeric_ordering() {
+        assert_eq!(compare("1.2", "1.10"), Ordering::Less);
+        assert_eq!(compare("2.0", "1.99.9"), Ordering::Greater);
+        assert_eq!(compare("10", "9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn alpha_segments() {
+        assert_eq!(compare("1.0a", "1.0b"), Ordering::Less);
+        assert_eq!(compare("1.0", "1.0a"), Ordering::Less);
+        // Numeric beats alphabetic.
+        assert_eq!(compare("1.1", "1.a"), Ordering::Greater);
+    }
+
+    #[test]
+    fn tilde_sorts_before_release() {
+        assert_eq!(compare("1.0~rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare("1.0~rc1", "1.0~rc2"), Ordering::Less);
+        assert_eq!(compare("1.0~~", "1.0~"), Ordering::Less);
+    }
+
+    #[test]
+    fn separators_are_insignificant() {
+        assert_eq!(compare("1.0.0", "1_0_0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_keys_agrees_with_compare() {
+        let versions = [
+            "1.0", "1.0.1", "1.10", "1.2", "2.0~rc1", "2.0", "1.0a", "0.99", "1.0~~", "1.0~rc1",
+        ];
+        for a in &versions {
+            for b in &versions {
+                assert_eq!(
+                    compare_keys(&sort_key(a), &sort_key(b)),
+                    compare(a, b),
+                    "key comparison diverged for {a} vs {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn epoch_is_the_most_significant_component() {
+        assert_eq!(compare("2:1.0", "1:9.9"), Ordering::Greater);
+        assert_eq!(compare("1.0", "0:1.0"), Ordering::Equal);
+        assert_eq!(compare("1:0.1", "9.9"), Ordering::Greater);
+        assert_eq!(compare("2:1.0", "2:1.1"), Ordering::Less);
+        // A colon without a numeric prefix is not an epoch separator.
+        assert_eq!(compare("a:1.0", "a:1.0"), Ordering::Equal);
+        assert_eq!(compare_str("2:1.0", "1:9.9"), Ordering::Greater);
+        assert_eq!(compare_str("1.0", "0:1.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn epoch_does_not_alter_the_remaining_components() {
+        let with_epoch = Version::parse("3:1.2~rc1").unwrap();
+        let without = Version::parse("1.2~rc1").unwrap();
+        assert_eq!(with_epoch.components()[1..], without.components()[1..]);
+        assert_eq!(with_epoch.to_string(), "3:1.2~rc1");
+    }
+
+    #[test]
+    fn version_type_orders_and_round_trips() {
+        let mut versions: Vec<Version> = ["2.0", "1.0~rc1", "1.10", "1.2", "1.0"]
+            .iter()
+            .map(|s| Version::parse(s).unwrap())
+            .collect();
+        versions.sort();
+        let sorted: Vec<&str> = versions.iter().map(Version::as_str).collect();
+        assert_eq!(sorted, ["1.0~rc1", "1.0", "1.2", "1.10", "2.0"]);
+        // Display reproduces the original spelling even where equality is
+        // looser than string equality.
+        assert_eq!(Version::parse("1.00").unwrap().to_string(), "1.00");
+        assert_eq!(
+            Version::parse("1.00").unwrap(),
+            Version::parse("1.0").unwrap()
+        );
+        assert_eq!(Version::parse(""), Err(VersionError::Empty));
+    }
+
+    #[test]
+    fn version_is_usable_as_a_btreemap_key() {
+        let mut releases = alloc::collections::BTreeMap::new();
+        releases.insert(Version::parse("1.10").unwrap(), "new");
+        releases.insert(Version::parse("1.9").unwrap(), "old");
+        let newest = releases.iter().next_back().unwrap();
+        assert_eq!(newest.0.as_str(), "1.10");
+    }
+
+    #[test]
+    fn compare_raw_agrees_with_the_component_path() {
+        for (a, b) in [
+            ("1.0", "1.0.1"),
+            ("", "1.0"),
+            ("", ""),
+            ("1.0~rc1", "1.0"),
+            ("2:1.0", "1:9.9"),
+            ("1.0a", "1.0.1"),
+            ("1.010", "1.10"),
+        ] {
+            assert_eq!(
+                compare_raw(a, b),
+                compare_keys(&sort_key(a), &sort_key(b)),
+                "diverged for {a:?} vs {b:?}"
+            );
+            assert_eq!(compare(a, b), compare_raw(a, b));
+        }
+    }
+
+    #[test]
+    fn version_req_matches_every_comparator() {
+        let matches = |req: &str, candidate: &str| VersionReq::parse(req).unwrap().matches(candidate);
+        assert!(matches("= 1.0", "1.0") && !matches("= 1.0", "1.1"));
+        assert!(matches("!= 1.0", "1.1") && !matches("!= 1.0", "1.0"));
+        assert!(matches("< 2.0", "1.9") && !matches("< 2.0", "2.0"));
+        assert!(matches("<= 2.0", "2.0") && !matches("<= 2.0", "2.1"));
+        assert!(matches("> 1.0", "1.0.1") && !matches("> 1.0", "1.0"));
+        assert!(matches(">= 1.0", "1.0") && !matches(">= 1.0", "0.9"));
+    }
+
+    #[test]
+    fn version_req_parse_rejects_malformed_input() {
+        assert!(VersionReq::parse(">= ").is_err());
+        assert!(VersionReq::parse("1.0").is_err());
+        assert!(VersionReq::parse("~> 1.0").is_err());
+        assert!(VersionReq::parse(">= 1.0 junk").is_err());
+        assert_eq!(VersionReq::parse("  >=   1.0 ").unwrap().to_string(), ">= 1.0");
+    }
+
+    #[test]
+    fn sort_versions_orders_oldest_first() {
+        let mut versions = vec!["2.0", "1.0~rc1", "1.10", "1.2", "1.0"];
+        sort_versions(&mut versions);
+        assert_eq!(versions, ["1.0~rc1", "1.0", "1.2", "1.10", "2.0"]);
+
+        let mut owned: Vec<String> = ["1.0", "2.0", "1.10", "1.9"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        sort_versions_owned(&mut owned);
+        // 1.10 is newer than 1.9: segments compare numerically, not
+        // lexicographically.
+        assert_eq!(owned, ["1.0", "1.9", "1.10", "2.0"]);
+    }
+
+    #[test]
+    fn satisfies_range_requires_every_constraint() {
+        // Open range.
+        assert!(satisfies_range("1.5", ">= 1.2"));
+        assert!(!satisfies_range("1.0", ">= 1.2"));
+        // Closed range.
+        assert!(satisfies_range("1.5", ">= 1.2, < 2.0"));
+        assert!(!satisfies_range("2.0", ">= 1.2, < 2.0"));
+        // Empty range is always satisfied.
+        assert!(satisfies_range("0.1", ""));
+        assert!(satisfies_range("0.1", " , "));
+        // Mutually exclusive constraints match nothing.
+        for candidate in ["0.5", "1.0", "9.9"] {
+            assert!(!satisfies_range(candidate, "< 1.0, > 2.0"));
+        }
+        // A malformed constraint is unsatisfiable.
+        assert!(!satisfies_range("1.0", "approximately 1.0"));
+    }
+
+    #[test]
+    fn latest_version_picks_the_newest() {
+        assert_eq!(latest_version(&["1.0", "2.0", "1.10", "1.9"]), Some("2.0"));
+        assert_eq!(latest_version(&["1.10", "1.9"]), Some("1.10"));
+        assert_eq!(latest_version(&[]), None);
+    }
+}
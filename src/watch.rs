@@ -0,0 +1,123 @@
+//! Filesystem-event cache invalidation.
+//!
+//! Only available with the `watch` feature. Long-lived consumers — build
+//! daemons, language servers — call [`Client::watch_changes`] to be told
+//! when a `.pc` file in the search path changes; the client's package
+//! cache entry for the affected package is evicted automatically, so the
+//! next query re-reads the file.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::client::Client;
+use crate::error::Result;
+
+/// Keeps a [`Client::watch_changes`] subscription alive.
+///
+/// Dropping the handle stops the watcher and releases its threads.
+#[derive(Debug)]
+pub struct WatchHandle {
+    _watcher: RecommendedWatcher,
+}
+
+impl Client {
+    /// Watches every directory in [`Client::search_paths`] for `.pc` file
+    /// changes, invoking `callback` with each affected path.
+    ///
+    /// Uses the platform's native event API (inotify on Linux, kqueue on
+    /// the BSDs and macOS) and falls back to polling where none exists.
+    /// Before the callback runs, the package cache entry matching the
+    /// changed file's stem is evicted. Search directories that do not
+    /// exist are skipped. Watching stops when the returned [`WatchHandle`]
+    /// is dropped.
+    pub fn watch_changes(
+        &self,
+        callback: impl Fn(PathBuf) + Send + 'static,
+    ) -> Result<WatchHandle> {
+        let cache = Arc::clone(self.cache());
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                let Ok(event) = result else {
+                    return;
+                };
+                for path in event.paths {
+                    if path.extension().is_none_or(|ext| ext != "pc") {
+                        continue;
+                    }
+                    if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                        cache.lock().unwrap().remove(stem);
+                    }
+                    callback(path);
+                }
+            })
+            .map_err(into_io)?;
+        for dir in self.search_paths() {
+            if dir.is_dir() {
+                watcher
+                    .watch(dir, RecursiveMode::NonRecursive)
+                    .map_err(into_io)?;
+            }
+        }
+        Ok(WatchHandle { _watcher: watcher })
+    }
+}
+
+/// Adapts watcher backend errors to the crate-wide error type.
+fn into_io(err: notify::Error) -> crate::error::PkgconfError {
+    crate::error::PkgconfError::Io(std::io::Error::other(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn changes_reach_the_callback_and_evict_the_cache() {
+        let dir = std::env::temp_dir().join(format!(
+            "libpkgconf-watch-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 1.0\nDescription: d\n",
+        )
+        .unwrap();
+        let mut client = Client::new();
+        client.set_search_dirs(&[&dir]);
+        client.resolve_package("foo", None).unwrap();
+        assert!(client.cache().lock().unwrap().contains("foo"));
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = client
+            .watch_changes(move |path| {
+                let _ = sender.send(path);
+            })
+            .unwrap();
+        std::fs::write(
+            dir.join("foo.pc"),
+            "Name: foo\nVersion: 2.0\nDescription: d\n",
+        )
+        .unwrap();
+        // Event delivery is asynchronous; wait for the foo.pc notification.
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let remaining = deadline
+                .saturating_duration_since(std::time::Instant::now());
+            let path = receiver
+                .recv_timeout(remaining)
+                .expect("no change event arrived");
+            if path.file_name().is_some_and(|name| name == "foo.pc") {
+                break;
+            }
+        }
+        assert!(!client.cache().lock().unwrap().contains("foo"));
+        assert_eq!(client.modversion("foo").unwrap(), "2.0");
+        drop(handle);
+    }
+}
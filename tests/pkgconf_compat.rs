@@ -0,0 +1,72 @@
+//! Compatibility regression suite against pkgconf reference output.
+//!
+//! Each fixture in `tests/pkgconf_compat/` is a pair of files: `NAME.pc`
+//! (the input) and `NAME.expected` (the `--cflags` and `--libs` output the
+//! C `libpkgconf` produces for it). The runner parses every `.pc` file,
+//! renders its flag fields through [`FragmentList`], and requires an exact
+//! match; any deviation from the reference behaviour is a test failure.
+
+#![cfg(feature = "std")]
+
+use std::fs;
+use std::path::Path;
+
+use libpkgconf::fragment::FragmentList;
+use libpkgconf::parser::{Keyword, PcFile};
+
+/// Parses a `NAME.expected` file into its `cflags:` and `libs:` lines.
+fn parse_expected(content: &str, path: &Path) -> (String, String) {
+    let mut cflags = None;
+    let mut libs = None;
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("cflags:") {
+            cflags = Some(rest.trim().to_owned());
+        } else if let Some(rest) = line.strip_prefix("libs:") {
+            libs = Some(rest.trim().to_owned());
+        }
+    }
+    match (cflags, libs) {
+        (Some(cflags), Some(libs)) => (cflags, libs),
+        _ => panic!("fixture {} is missing a cflags: or libs: line", path.display()),
+    }
+}
+
+/// Renders a flag field of `pc` the way `--cflags`/`--libs` would.
+fn render_field(pc: &PcFile, keyword: Keyword) -> String {
+    let field = pc.resolve_field(keyword).unwrap().unwrap_or_default();
+    FragmentList::parse(&field).unwrap().render(' ')
+}
+
+#[test]
+fn fixtures_match_pkgconf_reference_output() {
+    let fixture_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/pkgconf_compat");
+    let mut checked = 0;
+    let mut entries: Vec<_> = fs::read_dir(&fixture_dir)
+        .expect("fixture directory exists")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pc"))
+        .collect();
+    entries.sort();
+    for pc_path in entries {
+        let expected_path = pc_path.with_extension("expected");
+        let expected = fs::read_to_string(&expected_path)
+            .unwrap_or_else(|_| panic!("missing {}", expected_path.display()));
+        let (expected_cflags, expected_libs) = parse_expected(&expected, &expected_path);
+        let pc = PcFile::from_path(&pc_path)
+            .unwrap_or_else(|err| panic!("failed to parse {}: {err}", pc_path.display()));
+        assert_eq!(
+            render_field(&pc, Keyword::Cflags),
+            expected_cflags,
+            "cflags mismatch for {}",
+            pc_path.display()
+        );
+        assert_eq!(
+            render_field(&pc, Keyword::Libs),
+            expected_libs,
+            "libs mismatch for {}",
+            pc_path.display()
+        );
+        checked += 1;
+    }
+    assert!(checked >= 20, "expected at least 20 fixtures, found {checked}");
+}
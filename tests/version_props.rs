@@ -0,0 +1,69 @@
+//! Property-based checks for the RPM-style version comparison.
+//!
+//! `version::compare` must behave as a total order over arbitrary input —
+//! the unit tests in `src/version.rs` pin concrete orderings, while these
+//! properties guard the algebra: reflexivity, antisymmetry and
+//! transitivity, plus the classic tripwires that naive string comparison
+//! gets wrong.
+
+use std::cmp::Ordering;
+
+use libpkgconf::version::{compare, sort_versions_owned};
+use proptest::prelude::*;
+
+/// Realistic version spellings: dotted numerics with optional epoch,
+/// pre-release tilde and alphabetic suffixes.
+fn version_string() -> impl Strategy<Value = String> {
+    proptest::string::string_regex(
+        "([0-9]:)?[0-9]{1,3}(\\.[0-9]{1,3}){0,4}(~?[a-z]{1,4}[0-9]{0,2})?",
+    )
+    .unwrap()
+}
+
+proptest! {
+    #[test]
+    fn comparison_is_reflexive(a in "\\PC*") {
+        prop_assert_eq!(compare(&a, &a), Ordering::Equal);
+    }
+
+    #[test]
+    fn comparison_is_antisymmetric(a in "\\PC*", b in "\\PC*") {
+        prop_assert_eq!(compare(&a, &b), compare(&b, &a).reverse());
+    }
+
+    #[test]
+    fn comparison_is_transitive(
+        a in version_string(),
+        b in version_string(),
+        c in version_string(),
+    ) {
+        if compare(&a, &b).is_ge() && compare(&b, &c).is_ge() {
+            prop_assert!(compare(&a, &c).is_ge());
+        }
+    }
+
+    #[test]
+    fn sorting_yields_a_totally_ordered_sequence(
+        mut versions in proptest::collection::vec(version_string(), 0..32),
+    ) {
+        sort_versions_owned(&mut versions);
+        for pair in versions.windows(2) {
+            prop_assert!(compare(&pair[0], &pair[1]).is_le());
+        }
+    }
+}
+
+#[test]
+fn classic_tripwires_order_correctly() {
+    // Numeric segments compare as numbers, not strings.
+    assert_eq!(compare("1.10", "1.9"), Ordering::Greater);
+    // The epoch dominates everything after it.
+    assert_eq!(compare("2:1.0", "1:9.9"), Ordering::Greater);
+    // Leading zeroes do not matter within a numeric segment.
+    assert_eq!(compare("1.0", "1.00"), Ordering::Equal);
+    // rpmvercmp semantics: extra trailing segments make a version newer —
+    // pre-releases are spelled with `~`, which sorts before everything.
+    assert_eq!(compare("1.0", "1.0.0.0.0"), Ordering::Less);
+    assert_eq!(compare("1.0alpha", "1.0"), Ordering::Greater);
+    assert_eq!(compare("1.0~alpha", "1.0"), Ordering::Less);
+}